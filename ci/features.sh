@@ -0,0 +1,28 @@
+#!/bin/sh
+# Lints and tests each day on its own, alone and with each capability feature, then each
+# capability with no days at all, so a test or import that needs a day it doesn't gate on
+# fails here.
+set -eu
+
+cd "$(dirname "$0")/.."
+days="day1 day2 day3 day4 day5 day6 day7"
+capabilities="alloc-stats bench ffi json serde serve trace viz"
+
+check() {
+    features=$1
+    shift
+    echo "== --features '$features'" >&2
+    cargo clippy --all-targets --no-default-features --features "$features" "$@" -- -D warnings
+    cargo test --no-default-features --features "$features" "$@"
+}
+
+for day in $days; do
+    check "$day" "$@"
+    for capability in $capabilities; do
+        check "$day,$capability" "$@"
+    done
+done
+check "" "$@"
+for capability in $capabilities; do
+    check "$capability" "$@"
+done
@@ -0,0 +1,86 @@
+/*
+ * A C API over the solver registry.
+ *
+ * The header, `ffi/aoc.h`, is generated from `src/ffi.rs` by the `header` test, so document the
+ * API there rather than in the header. Update it with
+ * `AOC_WRITE_HEADER=1 cargo test --features ffi --lib ffi`.
+ *
+ * Build it as a shared library with
+ * `cargo rustc --release --lib --features ffi --crate-type cdylib`.
+ *
+ * Ownership: strings returned through `answer` out-parameters are allocated here and must be
+ * released with aoc_string_free(). Solver variant names are static and must not be freed.
+ * Input buffers are only borrowed for the duration of the call.
+ */
+
+#ifndef AOC_H
+#define AOC_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define AOC_OK 0
+/* A required pointer argument was `NULL`. */
+#define AOC_ERR_NULL 1
+/* The input or variant name wasn't valid UTF-8. */
+#define AOC_ERR_UTF8 2
+/* No solver is registered for that day, part and variant. */
+#define AOC_ERR_NOT_FOUND 3
+/* The input couldn't be parsed; `*answer` holds the error message. */
+#define AOC_ERR_GENERATING 4
+/* The solver failed on the parsed input; `*answer` holds the error message. */
+#define AOC_ERR_RUNNING 5
+
+/* A registered solver, as filled in by aoc_solver_get(). */
+typedef struct aoc_solver {
+    uint8_t day;
+    uint8_t part;
+    /* `NULL` for a day and part's only solver. */
+    const char *variant;
+} aoc_solver;
+
+/* The number of registered solvers. */
+size_t aoc_solver_count(void);
+
+/*
+ * Fills `out` with the solver at `index`, in registry order.
+ *
+ * Safety:
+ *
+ * `out` must be null or valid for writing an `aoc_solver`.
+ */
+int aoc_solver_get(size_t index, aoc_solver *out);
+
+/*
+ * Solves `input` with the solver for `day`, `part` and `variant` (`NULL` for the default).
+ *
+ * On success `*answer` is the answer, with one line per row for a grid. When the solver fails
+ * `*answer` is an error message instead, and for any other error it's `NULL`. Either way a
+ * non-null `*answer` must be released with aoc_string_free().
+ *
+ * Safety:
+ *
+ * `variant` must be null or a NUL-terminated string, `input` must be valid for reading `len`
+ * bytes, and `answer` must be valid for writing a pointer.
+ */
+int aoc_solve(uint8_t day, uint8_t part, const char *variant,
+              const uint8_t *input, size_t len, char **answer);
+
+/*
+ * Releases a string returned by aoc_solve(). Does nothing for `NULL`.
+ *
+ * Safety:
+ *
+ * `s` must be null or a string from aoc_solve() that hasn't been freed yet.
+ */
+void aoc_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AOC_H */
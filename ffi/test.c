@@ -0,0 +1,60 @@
+/* Exercises the C API. Run it with ffi/test.sh. */
+
+#include "aoc.h"
+
+#include <stdio.h>
+#include <string.h>
+
+static int failures = 0;
+
+#define CHECK(cond)                                                  \
+    do {                                                             \
+        if (!(cond)) {                                               \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,  \
+                    __LINE__, #cond);                                \
+            failures++;                                              \
+        }                                                            \
+    } while (0)
+
+static int solve(uint8_t day, uint8_t part, const char *variant,
+                 const char *input, const char *expected) {
+    char *answer = NULL;
+    int code = aoc_solve(day, part, variant, (const uint8_t *)input,
+                         strlen(input), &answer);
+    if (expected != NULL) {
+        CHECK(answer != NULL && strcmp(answer, expected) == 0);
+    }
+    aoc_string_free(answer);
+    return code;
+}
+
+int main(void) {
+    size_t count = aoc_solver_count();
+    CHECK(count > 0);
+
+    for (size_t i = 0; i < count; i++) {
+        aoc_solver solver;
+        CHECK(aoc_solver_get(i, &solver) == AOC_OK);
+        printf("day %u part %u %s\n", solver.day, solver.part,
+               solver.variant ? solver.variant : "(default)");
+    }
+    aoc_solver solver;
+    CHECK(aoc_solver_get(count, &solver) == AOC_ERR_NOT_FOUND);
+    CHECK(aoc_solver_get(0, NULL) == AOC_ERR_NULL);
+
+    CHECK(solve(6, 1, NULL, "3,4,3,1,2", "5934") == AOC_OK);
+    CHECK(solve(6, 2, NULL, "3,4,3,1,2", "26984457539") == AOC_OK);
+    CHECK(solve(1, 2, "fold",
+                "199\n200\n208\n210\n200\n207\n240\n269\n260\n263", "5") ==
+          AOC_OK);
+    CHECK(solve(7, 1, NULL, "1,x", NULL) == AOC_ERR_GENERATING);
+    CHECK(solve(1, 1, "nope", "1", NULL) == AOC_ERR_NOT_FOUND);
+    CHECK(aoc_solve(7, 1, NULL, NULL, 3, NULL) == AOC_ERR_NULL);
+
+    if (failures > 0) {
+        fprintf(stderr, "%d check(s) failed\n", failures);
+        return 1;
+    }
+    printf("all checks passed\n");
+    return 0;
+}
@@ -0,0 +1,14 @@
+#!/bin/sh
+# Builds the shared library and runs the C test program against it.
+set -eu
+
+cd "$(dirname "$0")/.."
+# Fails if ffi/aoc.h no longer matches src/ffi.rs.
+cargo test --lib --features ffi ffi::tests::header "$@"
+cargo rustc --release --lib --features ffi --crate-type cdylib "$@"
+
+out=target/ffi
+mkdir -p "$out"
+${CC:-cc} -Wall -Wextra -Werror -std=c99 -Iffi ffi/test.c \
+    -Ltarget/release -laoc -Wl,-rpath,"$PWD/target/release" -o "$out/test"
+"$out/test"
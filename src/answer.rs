@@ -0,0 +1,183 @@
+use crate::grid::Grid;
+use crate::ocr;
+use std::fmt;
+
+/// What a solver returns.
+///
+/// Integers are normalized on the way in: anything that fits an `i64` is an [`Answer::Integer`],
+/// so answers built from different integer types still compare equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Integer(i64),
+    /// An integer too big for an `i64`, of any size, in decimal: digits without leading zeros and
+    /// with a `-` in front if it's negative.
+    BigInteger(String),
+    Text(String),
+    /// A picture of letters, for puzzles whose answer is read off a picture.
+    Grid(Grid),
+}
+
+impl Answer {
+    /// The letters drawn in `pixels`, read with [`ocr::read`], for solvers whose answer is a
+    /// picture. If some letters can't be read the picture itself is the answer, so it can still be
+    /// read by eye.
+    #[must_use]
+    pub fn from_pixels(pixels: &Grid) -> Self {
+        ocr::read(pixels).map_or_else(|_| Answer::Grid(pixels.clone()), Answer::Text)
+    }
+
+    /// How the answer is written where it has to fit on one line, like an `.answers` file or a
+    /// table cell. Grid rows are separated by `/`.
+    #[must_use]
+    pub fn inline(&self) -> String {
+        match self {
+            Answer::Grid(grid) => grid.text("/"),
+            answer => answer.to_string(),
+        }
+    }
+
+    /// Reads an answer written by [`Answer::inline`] or [`Display`](fmt::Display). Anything made
+    /// of nothing but `#` and `.` is read as a grid, even a single row.
+    #[must_use]
+    pub fn parse(s: &str) -> Self {
+        let s = s.trim();
+        if let Some(n) = Self::integer(s) {
+            return n;
+        }
+        let rows: Vec<_> = s.split(['/', '\n']).map(str::trim).collect();
+        let pixels = |row: &&str| !row.is_empty() && row.chars().all(|c| c == '#' || c == '.');
+        if rows.iter().all(pixels) {
+            Answer::Grid(Grid::from_text(&rows.join("\n")))
+        } else {
+            Answer::Text(s.to_string())
+        }
+    }
+
+    // The integer written in decimal in `s`, however big.
+    fn integer(s: &str) -> Option<Self> {
+        if let Ok(n) = s.parse::<i64>() {
+            return Some(Answer::Integer(n));
+        }
+        let (sign, digits) = match s.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", s.strip_prefix('+').unwrap_or(s)),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        // Only a value that fits an `i64` could be all zeros, so there's always a digit left.
+        let digits = digits.trim_start_matches('0');
+        Some(Answer::BigInteger(format!("{sign}{digits}")))
+    }
+
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Integer(_) => "integer",
+            Answer::BigInteger(_) => "big_integer",
+            Answer::Text(_) => "text",
+            Answer::Grid(_) => "grid",
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(n) => write!(f, "{n}"),
+            Answer::BigInteger(n) => f.write_str(n),
+            Answer::Text(s) => f.write_str(s),
+            Answer::Grid(grid) => write!(f, "{grid}"),
+        }
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                i64::try_from(n).map_or_else(|_| Answer::BigInteger(n.to_string()), Answer::Integer)
+            }
+        }
+    )*};
+}
+
+from_int!(i32, i64, i128, u32, u64, u128);
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Self {
+        (n as u64).into()
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Answer;
+    use crate::grid::Grid;
+
+    #[test]
+    fn from() {
+        assert_eq!(Answer::from(7_u32), Answer::Integer(7));
+        assert_eq!(Answer::from(7_u64), Answer::from(7_i32));
+        assert_eq!(
+            Answer::from(u64::MAX),
+            Answer::BigInteger(u64::MAX.to_string())
+        );
+        assert_eq!(Answer::from(-7_i128), Answer::Integer(-7));
+        assert_eq!(
+            Answer::from(u128::MAX),
+            Answer::BigInteger(u128::MAX.to_string())
+        );
+        assert_eq!(
+            Answer::from("EFLFJGRF"),
+            Answer::Text("EFLFJGRF".to_string())
+        );
+    }
+
+    #[test]
+    fn from_pixels() {
+        let pixels =
+            Grid::from_text("#..#.###.\n#..#.#..#\n####.###.\n#..#.#..#\n#..#.#..#\n#..#.###.");
+        assert_eq!(Answer::from_pixels(&pixels), Answer::from("HB"));
+
+        let square = Grid::new(2, 2, true);
+        assert_eq!(Answer::from_pixels(&square), Answer::Grid(square));
+    }
+
+    #[test]
+    fn parse() {
+        let grid = Answer::Grid(Grid::from_text("#..#\n####"));
+        for answer in [
+            Answer::Integer(-3),
+            Answer::from(1_u128 << 100),
+            Answer::BigInteger(format!("-{}", "9".repeat(60))),
+            Answer::from("EFLFJGRF"),
+            grid.clone(),
+            Answer::Grid(Grid::from_text("#.#")),
+        ] {
+            assert_eq!(Answer::parse(&answer.inline()), answer);
+            assert_eq!(Answer::parse(&answer.to_string()), answer);
+        }
+        assert_eq!(grid.inline(), "#..#/####");
+        assert_eq!(grid.to_string(), "#..#\n####");
+        assert_eq!(Answer::parse("a/b"), Answer::from("a/b"));
+        assert_eq!(
+            Answer::parse(&format!("+000{}", u128::MAX)),
+            Answer::from(u128::MAX)
+        );
+        assert_eq!(Answer::parse("-00012"), Answer::Integer(-12));
+        assert_eq!(Answer::parse("-"), Answer::from("-"));
+    }
+}
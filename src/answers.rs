@@ -0,0 +1,127 @@
+use crate::answer::Answer;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Known answers for one input, read from an `.answers` file next to it:
+///
+/// ```text
+/// part1: 7
+/// part2: 5
+/// ```
+///
+/// Answers are written the way [`Answer::inline`] writes them, so a grid's rows are separated by
+/// `/`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Known {
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong { expected: String },
+    Unknown,
+}
+
+/// The `.answers` file that holds the known answers for `input`.
+#[must_use]
+pub fn path_for(input: &Path) -> PathBuf {
+    input.with_extension("answers")
+}
+
+/// Loads the known answers for `input`. A missing `.answers` file means nothing is known.
+///
+/// # Errors
+///
+/// Returns an error if the `.answers` file exists but can't be read.
+pub fn for_input(input: &Path) -> io::Result<Known> {
+    match fs::read_to_string(path_for(input)) {
+        Ok(contents) => Ok(Known::parse(&contents)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Known::default()),
+        Err(e) => Err(e),
+    }
+}
+
+impl Known {
+    #[must_use]
+    pub fn parse(contents: &str) -> Self {
+        let mut known = Self::default();
+        for (key, value) in contents.lines().filter_map(|l| l.split_once(':')) {
+            let value = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+            match key.trim() {
+                "part1" => known.part1 = value,
+                "part2" => known.part2 = value,
+                _ => {}
+            }
+        }
+        known
+    }
+
+    #[must_use]
+    pub fn get(&self, part: u8) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            2 => self.part2.as_deref(),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn verify(&self, part: u8, answer: &Answer) -> Verdict {
+        match self.get(part) {
+            Some(expected) if Answer::parse(expected) == *answer => Verdict::Correct,
+            Some(expected) => Verdict::Wrong {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Known, Verdict};
+    use crate::answer::Answer;
+    use crate::grid::Grid;
+
+    #[test]
+    fn parse() {
+        let known = Known::parse("part1: 7\npart2:\n");
+        assert_eq!(known.get(1), Some("7"));
+        assert_eq!(known.get(2), None);
+    }
+
+    #[test]
+    fn verify() {
+        let known = Known::parse("part1: 7\npart2: #..#/####");
+        assert_eq!(known.verify(1, &7.into()), Verdict::Correct);
+        assert_eq!(
+            known.verify(1, &6.into()),
+            Verdict::Wrong {
+                expected: "7".to_string()
+            }
+        );
+        assert_eq!(
+            known.verify(1, &"7".into()),
+            Verdict::Wrong {
+                expected: "7".to_string()
+            }
+        );
+        let grid = Answer::Grid(Grid::from_text("#..#\n####"));
+        assert_eq!(known.verify(2, &grid), Verdict::Correct);
+        let row = Answer::Grid(Grid::from_text(".##."));
+        assert_eq!(
+            Known::parse("part1: .##.").verify(1, &row),
+            Verdict::Correct
+        );
+        assert_eq!(Known::default().verify(2, &5.into()), Verdict::Unknown);
+    }
+
+    #[test]
+    fn fixtures() {
+        let known = super::for_input(&crate::run::fixture_dir(6).join("example.txt")).unwrap();
+        assert_eq!(known.get(2), Some("26984457539"));
+    }
+}
@@ -0,0 +1,333 @@
+use crate::registry::Solver;
+use crate::run;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// How large a t statistic has to be before a difference counts as real, roughly 95% confidence
+/// for the sample sizes we take.
+pub const T_CRITICAL: f64 = 2.0;
+pub const DEFAULT_SAMPLES: usize = 20;
+/// Fewer samples than this have no spread to test a difference against.
+pub const MIN_SAMPLES: usize = 2;
+/// Slowdowns below this percentage don't fail `bench --compare`, however significant.
+pub const DEFAULT_THRESHOLD: f64 = 10.0;
+
+/// Timings of repeated runs of one solver, in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+/// One line of the history file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub commit: String,
+    pub machine: String,
+    pub day: u8,
+    pub part: u8,
+    pub variant: String,
+    pub stats: Stats,
+}
+
+/// How a solver's latest timings compare with its baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    /// Percentage change in the mean; positive is slower.
+    pub change: f64,
+    /// Whether Welch's t-test says the means really differ.
+    pub significant: bool,
+    /// Whether either side had too few samples to test, in which case nothing is significant.
+    pub insufficient: bool,
+}
+
+/// An append-only file of bench results, one tab-separated [`Record`] per line.
+#[derive(Debug, Clone)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl Stats {
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn from_samples(samples: &[f64]) -> Self {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = if samples.len() > 1 {
+            samples.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1.0)
+        } else {
+            0.0
+        };
+        Self {
+            samples: samples.len(),
+            mean,
+            stddev: variance.sqrt(),
+        }
+    }
+
+    /// Compares these timings against `baseline`. Neither has to have [`MIN_SAMPLES`] for the
+    /// change to be worked out, but both do for it to be significant.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn compare(&self, baseline: &Stats) -> Comparison {
+        let change = (self.mean - baseline.mean) / baseline.mean * 100.0;
+        if self.samples < MIN_SAMPLES || baseline.samples < MIN_SAMPLES {
+            return Comparison {
+                change,
+                significant: false,
+                insufficient: true,
+            };
+        }
+        let error = (self.stddev.powi(2) / self.samples as f64
+            + baseline.stddev.powi(2) / baseline.samples as f64)
+            .sqrt();
+        let difference = self.mean - baseline.mean;
+        let significant = if error > 0.0 {
+            (difference / error).abs() > T_CRITICAL
+        } else {
+            difference != 0.0
+        };
+        Comparison {
+            change,
+            significant,
+            insufficient: false,
+        }
+    }
+}
+
+impl Comparison {
+    #[must_use]
+    pub fn is_regression(&self, threshold: f64) -> bool {
+        self.significant && self.change > threshold
+    }
+}
+
+/// Generates and solves `input` `samples` times, or returns `None` if the solver fails.
+#[must_use]
+pub fn measure(solver: &Solver, input: &str, samples: usize) -> Option<Stats> {
+    let mut timings = Vec::with_capacity(samples);
+    for _ in 0..samples.max(1) {
+        let run = run::run(solver, input);
+        run.answer.ok()?;
+        timings.push((run.generator + run.solver).as_secs_f64() * 1e9);
+    }
+    Some(Stats::from_samples(&timings))
+}
+
+impl Record {
+    fn to_line(&self) -> String {
+        let Stats {
+            samples,
+            mean,
+            stddev,
+        } = self.stats;
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{samples}\t{mean:.1}\t{stddev:.1}",
+            self.commit, self.machine, self.day, self.part, self.variant
+        )
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let mut next = || fields.next();
+        Some(Self {
+            commit: next()?.to_string(),
+            machine: next()?.to_string(),
+            day: next()?.parse().ok()?,
+            part: next()?.parse().ok()?,
+            variant: next()?.to_string(),
+            stats: Stats {
+                samples: next()?.parse().ok()?,
+                mean: next()?.parse().ok()?,
+                stddev: next()?.parse().ok()?,
+            },
+        })
+    }
+
+    #[must_use]
+    pub fn same_solver(&self, other: &Record) -> bool {
+        self.machine == other.machine
+            && self.day == other.day
+            && self.part == other.part
+            && self.variant == other.variant
+    }
+}
+
+impl History {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every record so far, oldest first. Lines that don't parse are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file exists but can't be read.
+    pub fn load(&self) -> io::Result<Vec<Record>> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(contents.lines().filter_map(Record::parse).collect()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the history file can't be created or written.
+    pub fn append(&self, records: &[Record]) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        for record in records {
+            writeln!(file, "{}", record.to_line())?;
+        }
+        Ok(())
+    }
+
+    /// The most recent record for the same machine and solver as `record`, from another commit.
+    #[must_use]
+    pub fn baseline<'a>(records: &'a [Record], record: &Record) -> Option<&'a Record> {
+        records
+            .iter()
+            .rev()
+            .find(|r| r.same_solver(record) && r.commit != record.commit)
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new("target/aoc-bench/history.tsv")
+    }
+}
+
+/// The commit being benchmarked, with `+` appended when the work tree has local changes.
+#[must_use]
+pub fn current_commit() -> String {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+    };
+    match git(&["rev-parse", "--short", "HEAD"]) {
+        Some(commit) if git(&["status", "--porcelain"]).is_some_and(|s| !s.is_empty()) => {
+            format!("{commit}+")
+        }
+        Some(commit) => commit,
+        None => "unknown".to_string(),
+    }
+}
+
+/// A name for this machine, so timings from different hardware aren't compared.
+#[must_use]
+pub fn machine() -> String {
+    std::env::var("AOC_MACHINE")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{History, Record, Stats};
+
+    fn record(commit: &str, mean: f64) -> Record {
+        Record {
+            commit: commit.to_string(),
+            machine: "box".to_string(),
+            day: 1,
+            part: 2,
+            variant: "fold".to_string(),
+            stats: Stats {
+                samples: 20,
+                mean,
+                stddev: 10.0,
+            },
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let stats = Stats::from_samples(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        assert_eq!(stats.samples, 8);
+        assert!((stats.mean - 5.0).abs() < 1e-9);
+        assert!((stats.stddev - (32.0_f64 / 7.0).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn compare() {
+        let baseline = record("a", 1000.0).stats;
+
+        let noise = record("b", 1004.0).stats.compare(&baseline);
+        assert!(!noise.significant);
+
+        let slower = record("b", 1200.0).stats.compare(&baseline);
+        assert!(slower.significant);
+        assert!((slower.change - 20.0).abs() < 1e-9);
+        assert!(slower.is_regression(10.0));
+        assert!(!slower.is_regression(25.0));
+
+        let faster = record("b", 800.0).stats.compare(&baseline);
+        assert!(faster.significant && !faster.is_regression(10.0));
+        assert!(!faster.insufficient);
+
+        // A single sample has no spread, so even a big difference can't be told from noise.
+        let once = Stats::from_samples(&[2000.0]).compare(&baseline);
+        assert!(once.insufficient && !once.significant && !once.is_regression(10.0));
+        assert!((once.change - 100.0).abs() < 1e-9);
+        assert!(
+            baseline
+                .compare(&Stats::from_samples(&[2000.0]))
+                .insufficient
+        );
+    }
+
+    #[test]
+    fn history() {
+        let dir = std::env::temp_dir().join(format!("aoc-bench-{}", std::process::id()));
+        let history = History::new(dir.join("history.tsv"));
+        assert!(history.load().unwrap().is_empty());
+
+        history
+            .append(&[record("a", 1000.0), record("b", 1100.0)])
+            .unwrap();
+        history.append(&[record("c", 1200.0)]).unwrap();
+        let records = history.load().unwrap();
+        assert_eq!(
+            records,
+            [
+                record("a", 1000.0),
+                record("b", 1100.0),
+                record("c", 1200.0)
+            ]
+        );
+
+        let current = record("c", 1300.0);
+        assert_eq!(History::baseline(&records, &current), Some(&records[1]));
+        let other_machine = Record {
+            machine: "laptop".to_string(),
+            ..current
+        };
+        assert_eq!(History::baseline(&records, &other_machine), None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,240 @@
+use std::any::Any;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+pub const DEFAULT_DIR: &str = "target/aoc-cache";
+
+const MAGIC: &[u8; 4] = b"aocc";
+
+/// A compact binary encoding for generator output, so parsed inputs can be stored on disk.
+pub trait Encode: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+    fn decode(input: &mut &[u8]) -> Option<Self>;
+}
+
+/// Stores encoded generator output keyed by day, crate version and a hash of the input.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, day: u8, input: &str) -> PathBuf {
+        self.dir.join(format!(
+            "day{day}-{}-{:016x}.bin",
+            env!("CARGO_PKG_VERSION"),
+            fnv1a(input.as_bytes())
+        ))
+    }
+
+    /// The cached generator output for `input`, if there is any.
+    #[must_use]
+    pub fn get(&self, day: u8, input: &str) -> Option<Vec<u8>> {
+        let contents = fs::read(self.path(day, input)).ok()?;
+        contents.strip_prefix(MAGIC).map(<[u8]>::to_vec)
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the cache directory or file can't be written.
+    pub fn put(&self, day: u8, input: &str, encoded: &[u8]) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        fs::write(self.path(day, input), [MAGIC.as_slice(), encoded].concat())
+    }
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new(DEFAULT_DIR)
+    }
+}
+
+// FNV-1a rather than `DefaultHasher`, whose output isn't guaranteed to be stable between releases.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Generator output along with its encoding.
+pub(crate) type Generated = (Box<dyn Any>, Vec<u8>);
+
+// Helpers the registry uses to drive a day's generator and solvers through the cache without
+// knowing the generator's output type. Building without any days leaves them unused.
+#[cfg_attr(
+    not(any(
+        feature = "day1",
+        feature = "day2",
+        feature = "day3",
+        feature = "day4",
+        feature = "day5",
+        feature = "day6",
+        feature = "day7"
+    )),
+    allow(dead_code)
+)]
+pub(crate) mod erased {
+    use super::{Encode, Generated};
+    use crate::answer::Answer;
+    use std::any::Any;
+
+    pub(crate) fn generate<T: Encode + 'static>(
+        input: &str,
+        generator: fn(&str) -> T,
+    ) -> Generated {
+        let parsed = generator(input);
+        let mut encoded = vec![];
+        parsed.encode(&mut encoded);
+        (Box::new(parsed), encoded)
+    }
+
+    pub(crate) fn decode<T: Encode + 'static>(
+        mut bytes: &[u8],
+        _generator: fn(&str) -> T,
+    ) -> Option<Box<dyn Any>> {
+        let parsed = T::decode(&mut bytes)?;
+        bytes.is_empty().then(|| Box::new(parsed) as Box<dyn Any>)
+    }
+
+    pub(crate) fn parse<T: 'static>(input: &str, generator: fn(&str) -> T) -> Box<dyn Any> {
+        Box::new(generator(input))
+    }
+
+    pub(crate) fn solve<T: 'static>(
+        parsed: &dyn Any,
+        _generator: fn(&str) -> T,
+        solver: impl Fn(&T) -> Answer,
+    ) -> Answer {
+        solver(parsed.downcast_ref().expect("generator output type"))
+    }
+}
+
+fn take<const N: usize>(input: &mut &[u8]) -> Option<[u8; N]> {
+    let (head, tail) = input.split_first_chunk()?;
+    *input = tail;
+    Some(*head)
+}
+
+macro_rules! encode_int {
+    ($($t:ty),*) => {$(
+        impl Encode for $t {
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn decode(input: &mut &[u8]) -> Option<Self> {
+                take(input).map(<$t>::from_le_bytes)
+            }
+        }
+    )*};
+}
+
+encode_int!(u8, u16, u32, u64);
+
+impl Encode for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(u8::from(*self));
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Encode> Encode for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (self.len() as u64).encode(out);
+        for item in self {
+            item.encode(out);
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let len = usize::try_from(u64::decode(input)?).ok()?;
+        // Don't trust the length enough to preallocate it; a corrupt file could claim anything.
+        let mut items = Vec::with_capacity(len.min(input.len()));
+        for _ in 0..len {
+            items.push(T::decode(input)?);
+        }
+        Some(items)
+    }
+}
+
+impl<T: Encode + Copy + Default, const N: usize> Encode for [T; N] {
+    fn encode(&self, out: &mut Vec<u8>) {
+        for item in self {
+            item.encode(out);
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        let mut items = [T::default(); N];
+        for item in &mut items {
+            *item = T::decode(input)?;
+        }
+        Some(items)
+    }
+}
+
+impl<A: Encode, B: Encode> Encode for (A, B) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some((A::decode(input)?, B::decode(input)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cache, Encode};
+
+    fn round_trip<T: Encode + PartialEq + std::fmt::Debug>(value: &T) {
+        let mut encoded = vec![];
+        value.encode(&mut encoded);
+        let mut bytes = encoded.as_slice();
+        assert_eq!(T::decode(&mut bytes).as_ref(), Some(value));
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    fn encode() {
+        round_trip(&(vec![1_u8, 2, 3], vec![[true, false], [false, true]]));
+        round_trip(&vec![u64::MAX, 0, 42]);
+        round_trip(&vec![vec![0xbeef_u16]]);
+    }
+
+    #[test]
+    fn truncated() {
+        let mut encoded = vec![];
+        vec![1_u32, 2, 3].encode(&mut encoded);
+        encoded.pop();
+        assert_eq!(Vec::<u32>::decode(&mut encoded.as_slice()), None);
+    }
+
+    #[test]
+    fn cache() {
+        let dir = std::env::temp_dir().join(format!("aoc-cache-{}", std::process::id()));
+        let cache = Cache::new(&dir);
+
+        assert_eq!(cache.get(1, "1\n2"), None);
+        cache.put(1, "1\n2", &[1, 2, 3]).unwrap();
+        assert_eq!(cache.get(1, "1\n2"), Some(vec![1, 2, 3]));
+        assert_eq!(cache.get(1, "1\n3"), None);
+        assert_eq!(cache.get(2, "1\n2"), None);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
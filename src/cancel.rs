@@ -0,0 +1,81 @@
+use std::cell::RefCell;
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Lets the runner ask a solver that has overrun its deadline to stop.
+///
+/// Cancellation is cooperative: long loops call [`check`] with the token, which unwinds out of the
+/// solver once cancellation has been requested.
+#[derive(Debug, Clone, Default)]
+pub struct Token(Arc<AtomicBool>);
+
+/// The unwind payload [`check`] stops a cancelled solver with.
+#[derive(Debug)]
+pub struct Cancelled;
+
+thread_local! {
+    static CURRENT: RefCell<Option<Token>> = const { RefCell::new(None) };
+}
+
+impl Token {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The token for the solver running on this thread, if the runner gave it one.
+#[must_use]
+pub fn current() -> Option<Token> {
+    CURRENT.with(|c| c.borrow().clone())
+}
+
+pub(crate) fn set_current(token: Option<Token>) {
+    CURRENT.with(|c| *c.borrow_mut() = token);
+}
+
+/// Unwinds with [`Cancelled`] if `token` has been cancelled.
+///
+/// This deliberately doesn't panic, so no panic message is printed for a solver that was stopped.
+pub fn check(token: Option<&Token>) {
+    if token.is_some_and(Token::is_cancelled) {
+        panic::resume_unwind(Box::new(Cancelled));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Cancelled, Token};
+    use std::panic;
+
+    #[test]
+    fn check() {
+        let token = Token::new();
+        super::check(None);
+        super::check(Some(&token));
+
+        token.clone().cancel();
+        let payload = panic::catch_unwind(|| super::check(Some(&token))).unwrap_err();
+        assert!(payload.is::<Cancelled>());
+    }
+
+    #[test]
+    fn current() {
+        assert!(super::current().is_none());
+        let token = Token::new();
+        super::set_current(Some(token.clone()));
+        token.cancel();
+        assert!(super::current().unwrap().is_cancelled());
+        super::set_current(None);
+    }
+}
@@ -0,0 +1,180 @@
+//! Solvers that can run at compile time, for the days that are nothing but arithmetic.
+// Without either day there's nothing to parse or check.
+#![cfg_attr(not(any(feature = "day1", feature = "day6")), allow(dead_code))]
+
+/// Solves `part` of `day` for the input file at `path` while compiling, giving a `u64` that can
+/// be used in a `const`:
+///
+/// ```text
+/// const ANSWER: u64 = aoc::const_answer!(day6, 2, "../input/2021/day6.txt");
+/// ```
+///
+/// `path` is read with [`include_str!`], so it's relative to the file the macro is used in. Only
+/// the days with a function of the same name in this module can be solved this way.
+#[macro_export]
+macro_rules! const_answer {
+    ($day:ident, $part:literal, $path:expr) => {
+        $crate::comptime::$day(include_str!($path), $part)
+    };
+}
+
+/// Day 1, counting depth increases one at a time or in windows of three.
+///
+/// # Panics
+///
+/// Panics if `input` isn't a list of numbers or `part` isn't 1 or 2.
+#[cfg(feature = "day1")]
+#[must_use]
+pub const fn day1(input: &str, part: u8) -> u64 {
+    match part {
+        1 => crate::day1::increases::<1>(input),
+        2 => crate::day1::increases::<3>(input),
+        _ => panic!("day 1 only has two parts"),
+    }
+}
+
+/// Day 6, simulating the lanternfish for 80 or 256 days.
+///
+/// # Panics
+///
+/// Panics if `input` isn't a list of numbers or `part` isn't 1 or 2.
+#[cfg(feature = "day6")]
+#[must_use]
+pub const fn day6(input: &str, part: u8) -> u64 {
+    let days = match part {
+        1 => 80,
+        2 => 256,
+        _ => panic!("day 6 only has two parts"),
+    };
+    crate::day6::Fish::parse(input).after_days(days).count()
+}
+
+/// The next number in `bytes` at or after `start`, and where it ends. Whitespace and commas
+/// between numbers are skipped.
+///
+/// # Panics
+///
+/// Panics on anything else or on a number too large for a `u64`, which stops the build when it
+/// happens at compile time.
+pub(crate) const fn next_number(bytes: &[u8], start: usize) -> Option<(u64, usize)> {
+    let mut i = start;
+    while i < bytes.len() && matches!(bytes[i], b' ' | b',' | b'\n' | b'\r' | b'\t') {
+        i += 1;
+    }
+    if i == bytes.len() {
+        return None;
+    }
+    assert!(bytes[i].is_ascii_digit(), "expected a number");
+    let mut n = 0;
+    while i < bytes.len() && bytes[i].is_ascii_digit() {
+        let digit = (bytes[i] - b'0') as u64;
+        assert!(n <= (u64::MAX - digit) / 10, "number too large");
+        n = n * 10 + digit;
+        i += 1;
+    }
+    Some((n, i))
+}
+
+/// The answer to `part` in the contents of an [`.answers`](crate::answers::Known) file.
+///
+/// # Panics
+///
+/// Panics if the answer isn't there or isn't a number.
+const fn known(answers: &str, part: u8) -> u64 {
+    let bytes = answers.as_bytes();
+    let mut i = 0;
+    while i + 6 < bytes.len() {
+        let line_start = i == 0 || bytes[i - 1] == b'\n';
+        if line_start
+            && bytes[i] == b'p'
+            && bytes[i + 1] == b'a'
+            && bytes[i + 2] == b'r'
+            && bytes[i + 3] == b't'
+            && bytes[i + 4] == b'0' + part
+            && bytes[i + 5] == b':'
+        {
+            match next_number(bytes, i + 6) {
+                Some((n, _)) => return n,
+                None => break,
+            }
+        }
+        i += 1;
+    }
+    panic!("no known answer")
+}
+
+// Checks every compile-time solver against the example's known answers, so a regression fails
+// the build rather than a test.
+macro_rules! check_examples {
+    ($($(#[$cfg:meta])* $day:ident),*) => {$(
+        $(#[$cfg])*
+        const _: () = {
+            let answers = include_str!(concat!("../fixtures/2021/", stringify!($day), "/example.answers"));
+            let input = include_str!(concat!("../fixtures/2021/", stringify!($day), "/example.txt"));
+            assert!($day(input, 1) == known(answers, 1));
+            assert!($day(input, 2) == known(answers, 2));
+        };
+    )*};
+}
+
+check_examples!(
+    #[cfg(feature = "day1")]
+    day1,
+    #[cfg(feature = "day6")]
+    day6
+);
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn next_number() {
+        let bytes = b"3,4\n 12\r\n";
+        assert_eq!(super::next_number(bytes, 0), Some((3, 1)));
+        assert_eq!(super::next_number(bytes, 1), Some((4, 3)));
+        assert_eq!(super::next_number(bytes, 3), Some((12, 7)));
+        assert_eq!(super::next_number(bytes, 7), None);
+
+        let max = u64::MAX.to_string();
+        assert_eq!(super::next_number(max.as_bytes(), 0), Some((u64::MAX, 20)));
+        let too_large = std::panic::catch_unwind(|| super::next_number(b"18446744073709551616", 0));
+        assert!(too_large.is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "day6")]
+    fn const_answer() {
+        const ANSWER: u64 = const_answer!(day6, 2, "../fixtures/2021/day6/example.txt");
+        assert_eq!(ANSWER, 26_984_457_539);
+    }
+
+    #[test]
+    fn known() {
+        let answers = "part1: 7\npart2: 26984457539\n";
+        assert_eq!(super::known(answers, 1), 7);
+        assert_eq!(super::known(answers, 2), 26_984_457_539);
+    }
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day6"))]
+    fn matches_registry() {
+        use crate::answer::Answer;
+        use crate::random::{self, Rng};
+        use crate::registry;
+
+        let mut rng = Rng::new(46);
+        for (day, solve) in [(1, super::day1 as fn(&str, u8) -> u64), (6, super::day6)] {
+            for size in [3, 10, 100] {
+                let input = random::input(day, size, &mut rng).unwrap();
+                for part in [1, 2] {
+                    let solver = registry::find(day, part, None).unwrap();
+                    let expected = solver.solve(&*solver.parse(&input));
+                    assert_eq!(
+                        Answer::from(solve(&input, part)),
+                        expected,
+                        "day {day} part {part}"
+                    );
+                }
+            }
+        }
+    }
+}
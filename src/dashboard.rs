@@ -0,0 +1,354 @@
+use crate::answers::{self, Verdict};
+use crate::registry::{Solver, SOLVERS};
+use crate::run::{self, Failure, Options, Run};
+use crate::trace;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+const CLEAR: &str = "\x1b[2J\x1b[H";
+const REVERSE: &str = "\x1b[7m";
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+const HELP: &str =
+    "j/k: move  N: select row  r: run  a: run all  t: trace  s: switch input  q: quit";
+
+/// Where the dashboard reads each day's input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSet {
+    /// The real puzzle inputs, from [`run::input_path`].
+    Puzzle,
+    /// The fixture with this file stem in each day's fixture directory, e.g. `example`.
+    Fixture(String),
+}
+
+/// A table of every registered solver with its last answer, verdict and timing.
+///
+/// Interactively, rows are selected and run one at a time; otherwise [`Dashboard::plain`] runs
+/// everything once and prints the table.
+pub struct Dashboard {
+    sets: Vec<InputSet>,
+    set: usize,
+    rows: Vec<Row>,
+    selected: usize,
+    options: Options,
+}
+
+struct Row {
+    solver: &'static Solver,
+    run: Option<Run>,
+    verdict: Verdict,
+    missing: bool,
+}
+
+impl InputSet {
+    #[must_use]
+    pub fn path(&self, day: u8) -> PathBuf {
+        match self {
+            InputSet::Puzzle => run::input_path(day),
+            InputSet::Fixture(stem) => run::fixture_dir(day).join(format!("{stem}.txt")),
+        }
+    }
+
+    /// The puzzle inputs, then every fixture name found for any day.
+    #[must_use]
+    pub fn all() -> Vec<Self> {
+        let stems: BTreeSet<_> = SOLVERS
+            .iter()
+            .flat_map(|s| run::fixtures(s.day).unwrap_or_default())
+            .filter_map(|p| Some(p.file_stem()?.to_str()?.to_string()))
+            .collect();
+        let mut sets = vec![InputSet::Puzzle];
+        sets.extend(stems.into_iter().map(InputSet::Fixture));
+        sets
+    }
+}
+
+impl Dashboard {
+    /// Solvers are always run with tracing on, so any row's trace can be shown.
+    #[must_use]
+    pub fn new(options: Options) -> Self {
+        Self {
+            sets: InputSet::all(),
+            set: 0,
+            rows: SOLVERS
+                .iter()
+                .map(|solver| Row {
+                    solver,
+                    run: None,
+                    verdict: Verdict::Unknown,
+                    missing: false,
+                })
+                .collect(),
+            selected: 0,
+            options: Options {
+                trace: true,
+                ..options
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn input_set(&self) -> &InputSet {
+        &self.sets[self.set]
+    }
+
+    /// Moves to the next input set, forgetting every row's results.
+    pub fn next_input_set(&mut self) {
+        self.set = (self.set + 1) % self.sets.len();
+        for row in &mut self.rows {
+            row.run = None;
+            row.verdict = Verdict::Unknown;
+            row.missing = false;
+        }
+    }
+
+    pub fn select(&mut self, row: usize) {
+        self.selected = row.min(self.rows.len().saturating_sub(1));
+    }
+
+    pub fn run_selected(&mut self) {
+        if self.selected < self.rows.len() {
+            self.run_row(self.selected);
+        }
+    }
+
+    pub fn run_all(&mut self) {
+        for row in 0..self.rows.len() {
+            self.run_row(row);
+        }
+    }
+
+    fn run_row(&mut self, row: usize) {
+        let path = self.input_set().path(self.rows[row].solver.day);
+        let row = &mut self.rows[row];
+        let Ok(input) = fs::read_to_string(&path) else {
+            row.missing = true;
+            return;
+        };
+        let run = run::run_with(row.solver, &input, &self.options);
+        row.verdict = match &run.answer {
+            Ok(answer) => answers::for_input(&path)
+                .unwrap_or_default()
+                .verify(row.solver.part, answer),
+            Err(_) => Verdict::Unknown,
+        };
+        row.missing = false;
+        row.run = Some(run);
+    }
+
+    /// Writes the table, highlighting the selected row and colouring verdicts when `ansi` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn render(&self, out: &mut impl Write, ansi: bool) -> io::Result<()> {
+        let set = match self.input_set() {
+            InputSet::Puzzle => "puzzle".to_string(),
+            InputSet::Fixture(stem) => stem.clone(),
+        };
+        writeln!(out, "Advent of code {} - input: {set}\n", run::YEAR)?;
+        writeln!(
+            out,
+            "  {:>2}  {:>3} {:>4}  {:<24} {:<16} {:<12} {:>12}",
+            "#", "Day", "Part", "Variant", "Answer", "Status", "Time"
+        )?;
+
+        for (i, row) in self.rows.iter().enumerate() {
+            let (answer, status, colour) = row.describe();
+            let time = row
+                .run
+                .as_ref()
+                .map(|r| format!("{:?}", r.generator + r.solver))
+                .unwrap_or_default();
+            let line = format!(
+                "{} {:>2}  {:>3} {:>4}  {:<24} {:<16} {:<12} {:>12}",
+                if i == self.selected { '>' } else { ' ' },
+                i + 1,
+                row.solver.day,
+                row.solver.part,
+                row.solver.variant.unwrap_or("default"),
+                answer,
+                status,
+                time
+            );
+            let style = if i == self.selected {
+                Some(REVERSE)
+            } else {
+                colour
+            };
+            match style.filter(|_| ansi) {
+                Some(style) => writeln!(out, "{style}{line}{RESET}")?,
+                None => writeln!(out, "{line}")?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the trace recorded the last time the selected row ran.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn explain(&self, out: &mut impl Write) -> io::Result<()> {
+        let Some(row) = self.rows.get(self.selected) else {
+            return writeln!(out, "no solvers");
+        };
+        writeln!(out, "{}", row.solver)?;
+        match &row.run {
+            None => writeln!(out, "not run yet"),
+            Some(run) if run.trace.is_empty() => {
+                writeln!(out, "no trace recorded (build with --features trace)")
+            }
+            Some(run) => trace::write_tree(out, &run.trace),
+        }
+    }
+
+    /// Runs everything once and prints the table, for when stdout isn't a terminal.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn plain(&mut self, out: &mut impl Write) -> io::Result<()> {
+        self.run_all();
+        self.render(out, false)?;
+        out.flush()
+    }
+
+    /// Redraws the table after each command read from `input`, one per line, until `q` or the end
+    /// of `input`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading `input` or writing to `out` fails.
+    pub fn interact(&mut self, input: impl BufRead, out: &mut impl Write) -> io::Result<()> {
+        let mut lines = input.lines();
+        let mut trace = false;
+        loop {
+            write!(out, "{CLEAR}")?;
+            self.render(out, true)?;
+            if trace {
+                writeln!(out)?;
+                self.explain(out)?;
+                trace = false;
+            }
+            write!(out, "\n{HELP}\n> ")?;
+            out.flush()?;
+
+            let Some(line) = lines.next().transpose()? else {
+                return Ok(());
+            };
+            match line.trim() {
+                "q" => return Ok(()),
+                "j" => self.select(self.selected + 1),
+                "k" => self.select(self.selected.saturating_sub(1)),
+                "r" | "" => self.run_selected(),
+                "a" => self.run_all(),
+                "t" => trace = true,
+                "s" => self.next_input_set(),
+                other => {
+                    if let Ok(row) = other.parse::<usize>() {
+                        self.select(row.saturating_sub(1));
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Row {
+    fn describe(&self) -> (String, String, Option<&'static str>) {
+        if self.missing {
+            return (String::new(), "no input".to_string(), None);
+        }
+        let Some(run) = &self.run else {
+            return (String::new(), "not run".to_string(), None);
+        };
+        match (&run.answer, &self.verdict) {
+            (Ok(answer), Verdict::Correct) => (answer.inline(), "correct".to_string(), Some(GREEN)),
+            (Ok(answer), Verdict::Wrong { expected }) => {
+                (answer.inline(), format!("wrong ({expected})"), Some(RED))
+            }
+            (Ok(answer), Verdict::Unknown) => (answer.inline(), "unverified".to_string(), None),
+            (Err(Failure::TimedOut(_)), _) => (String::new(), "timed out".to_string(), Some(RED)),
+            (Err(failure), _) => (
+                String::new(),
+                format!("failed {}", failure.stage()),
+                Some(RED),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Dashboard;
+    use crate::run::Options;
+
+    #[cfg(any(feature = "day1", feature = "day4"))]
+    fn example() -> Dashboard {
+        use super::InputSet;
+
+        let mut dashboard = Dashboard::new(Options::default());
+        let example = InputSet::Fixture("example".to_string());
+        let set = dashboard.sets.iter().position(|s| *s == example);
+        for _ in 0..set.expect("no example fixtures") {
+            dashboard.next_input_set();
+        }
+        dashboard
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn plain() {
+        let mut out = vec![];
+        example().plain(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.starts_with("Advent of code 2021 - input: example\n"));
+        assert!(!out.contains('\x1b'));
+        let rows: Vec<_> = out.lines().skip(3).collect();
+        assert_eq!(rows.len(), crate::registry::SOLVERS.len());
+        assert!(rows.iter().all(|r| r.contains(" correct ")), "{out}");
+        assert!(rows[0].starts_with(">  1    1    1  for_loop"));
+    }
+
+    #[test]
+    #[cfg(feature = "day4")]
+    fn interact() {
+        let row = crate::registry::SOLVERS
+            .iter()
+            .position(|s| s.day == 4 && s.part == 2)
+            .unwrap()
+            + 1;
+        let mut dashboard = example();
+        let mut out = vec![];
+        let keys = format!("{row}\nr\nt\nq\n");
+        dashboard.interact(keys.as_bytes(), &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let last = out.rsplit(super::CLEAR).next().unwrap();
+
+        assert!(last.contains(&format!(
+            "> {row:>2}    4    2  default                  1924"
+        )));
+        assert!(last.contains("Day 4 - Part 2\n"));
+        assert!(last.contains(&format!(
+            " {:>2}    4    1  default                                   not run",
+            row - 1
+        )));
+    }
+
+    #[test]
+    fn empty() {
+        let mut dashboard = Dashboard::new(Options::default());
+        dashboard.rows.clear();
+        dashboard.select(3);
+        dashboard.run_selected();
+        let mut out = vec![];
+        dashboard.explain(&mut out).unwrap();
+        assert_eq!(out, b"no solvers\n");
+    }
+}
@@ -1,3 +1,7 @@
+use crate::answer::Answer;
+use crate::comptime;
+use std::cmp::Ordering;
+
 #[aoc_generator(day1)]
 pub fn input_generator(input: &str) -> Vec<u32> {
     input
@@ -6,120 +10,407 @@ pub fn input_generator(input: &str) -> Vec<u32> {
         .collect()
 }
 
-#[aoc(day1, part1, for_loop)]
-pub fn part1_loop(input: &[u32]) -> u32 {
-    let mut increases = 0;
-    let mut last = input[0];
-    for depth in input {
-        if depth > &last {
-            increases += 1;
+/// How a window of depths is compared with the window before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Greater,
+    GreaterOrEqual,
+    Less,
+}
+
+impl Comparison {
+    fn holds<T: PartialOrd>(self, later: &T, earlier: &T) -> bool {
+        match self {
+            Comparison::Greater => later > earlier,
+            Comparison::GreaterOrEqual => later >= earlier,
+            Comparison::Less => later < earlier,
         }
-        last = *depth;
     }
-    increases
 }
 
-#[aoc(day1, part1, fold)]
-pub fn part1_fold(input: &[u32]) -> u32 {
-    let mut last = input[0];
-    input.iter().fold(0_u32, |mut i, &c| {
-        if c > last {
-            i += 1;
+/// How many sums of `window` consecutive items compare to the sum of the window one item earlier
+/// as `comparison` asks.
+///
+/// # Panics
+///
+/// Panics if `window` is 0.
+pub fn count_windows<T: PartialOrd>(items: &[T], window: usize, comparison: Comparison) -> usize {
+    let mut count = 0;
+    for (earlier, later) in window_edges(items, window) {
+        if comparison.holds(later, earlier) {
+            count += 1;
         }
-        last = c;
-        i
-    })
+    }
+    count
 }
 
-#[aoc(day1, part2, for_loop)]
-pub fn part2_loop(input: &[u32]) -> u32 {
-    let mut last: u32 = input.windows(3).next().unwrap().iter().sum();
-    let mut increases = 0;
+// Neighbouring windows share all but their first and last items, so comparing their sums is the
+// same as comparing `items[i + window]` with `items[i]`, and nothing has to be added up. These
+// are those pairs of items.
+fn window_edges<T>(items: &[T], window: usize) -> impl Iterator<Item = (&T, &T)> {
+    assert!(window > 0, "windows need at least one item");
+    items.iter().zip(items.iter().skip(window))
+}
 
-    for i in input.windows(3) {
-        let sum = i.iter().sum();
+fn fold_windows(input: &[u32], window: usize) -> usize {
+    window_edges(input, window).fold(0, |increases, (leaving, entering)| {
+        increases + usize::from(Comparison::Greater.holds(entering, leaving))
+    })
+}
 
-        if sum > last {
-            increases += 1;
-        }
+#[aoc(day1, part1, for_loop)]
+pub fn part1_loop(input: &[u32]) -> Answer {
+    count_windows(input, 1, Comparison::Greater).into()
+}
 
-        last = sum;
-    }
+#[aoc(day1, part1, fold)]
+pub fn part1_fold(input: &[u32]) -> Answer {
+    fold_windows(input, 1).into()
+}
 
-    increases
+#[aoc(day1, part2, for_loop)]
+pub fn part2_loop(input: &[u32]) -> Answer {
+    count_windows(input, 3, Comparison::Greater).into()
 }
 
 #[aoc(day1, part2, fold)]
-pub fn part2_fold(input: &[u32]) -> u32 {
-    let mut last: u32 = input.windows(3).next().unwrap().iter().sum();
+pub fn part2_fold(input: &[u32]) -> Answer {
+    fold_windows(input, 3).into()
+}
 
-    input.windows(3).fold(0, |mut increases, n| {
-        let sum = n.iter().sum();
-        if sum > last {
-            increases += 1;
+/// How many depths in `input` are deeper than the one `WINDOW` before them, which is how many
+/// times the sum of a sliding window of `WINDOW` depths increases. Parses as it goes, so it can
+/// run at compile time.
+pub const fn increases<const WINDOW: usize>(input: &str) -> u64 {
+    let bytes = input.as_bytes();
+    let mut window = [0; WINDOW];
+    let (mut seen, mut count, mut i) = (0, 0, 0);
+    while let Some((depth, next)) = comptime::next_number(bytes, i) {
+        if seen >= WINDOW && depth > window[seen % WINDOW] {
+            count += 1;
         }
-        last = sum;
-        increases
-    })
+        window[seen % WINDOW] = depth;
+        seen += 1;
+        i = next;
+    }
+    count
 }
 
-// Try out part1 again with this weird struct to handle state
+/// How many times a value went up, went down or stayed the same from one to the next.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Trend {
+    pub increases: u64,
+    pub decreases: u64,
+    pub plateaus: u64,
+}
 
-struct IncreaseCounter {
+/// Follows a sonar sweep one depth at a time, for streams of depths too long to hold.
+///
+/// Only the last `WINDOW` depths are kept, for the trend of the sums of each `WINDOW` depths in a
+/// row, so memory use doesn't grow however many depths are submitted.
+#[derive(Debug, Clone)]
+pub struct SonarAnalyzer<const WINDOW: usize = 3> {
+    depths: u64,
     last: u32,
-    count: u32,
+    min: u32,
+    max: u32,
+    trend: Trend,
+    // Which way the depths are heading, and for how many depths they have.
+    run: (Ordering, u64),
+    longest_increasing: u64,
+    longest_decreasing: u64,
+    window: [u32; WINDOW],
+    // Where the next depth goes in `window`, replacing the oldest.
+    slot: usize,
+    window_sum: u64,
+    window_trend: Trend,
+}
+
+impl Trend {
+    fn record(&mut self, ordering: Ordering) {
+        match ordering {
+            Ordering::Greater => self.increases += 1,
+            Ordering::Less => self.decreases += 1,
+            Ordering::Equal => self.plateaus += 1,
+        }
+    }
 }
 
-impl IncreaseCounter {
-    fn new(initial: u32) -> Self {
+impl<const WINDOW: usize> SonarAnalyzer<WINDOW> {
+    #[must_use]
+    pub fn new() -> Self {
+        const { assert!(WINDOW > 0, "windows need at least one depth") };
         Self {
-            last: initial,
-            count: 0,
+            depths: 0,
+            last: 0,
+            min: u32::MAX,
+            max: 0,
+            trend: Trend::default(),
+            run: (Ordering::Equal, 0),
+            longest_increasing: 0,
+            longest_decreasing: 0,
+            window: [0; WINDOW],
+            slot: 0,
+            window_sum: 0,
+            window_trend: Trend::default(),
         }
     }
-    fn submit(&mut self, i: u32) {
-        if i > self.last {
-            self.count += 1;
+
+    pub fn submit(&mut self, depth: u32) {
+        if self.depths == 0 {
+            self.run = (Ordering::Equal, 1);
+            self.longest_increasing = 1;
+            self.longest_decreasing = 1;
+        } else {
+            let ordering = depth.cmp(&self.last);
+            self.trend.record(ordering);
+            let (heading, len) = self.run;
+            let len = match ordering {
+                Ordering::Equal => 1,
+                _ if ordering == heading => len + 1,
+                _ => 2,
+            };
+            self.run = (ordering, len);
+            if ordering == Ordering::Greater {
+                self.longest_increasing = self.longest_increasing.max(len);
+            } else if ordering == Ordering::Less {
+                self.longest_decreasing = self.longest_decreasing.max(len);
+            }
+        }
+
+        // The window sums before and after this depth share every depth but the one it replaces.
+        let leaving = self.window[self.slot];
+        if self.depths >= WINDOW as u64 {
+            self.window_trend.record(depth.cmp(&leaving));
+            self.window_sum -= u64::from(leaving);
+        }
+        self.window_sum += u64::from(depth);
+        self.window[self.slot] = depth;
+        self.slot = (self.slot + 1) % WINDOW;
+
+        self.depths += 1;
+        self.last = depth;
+        self.min = self.min.min(depth);
+        self.max = self.max.max(depth);
+    }
+
+    /// How many depths have been submitted.
+    #[must_use]
+    pub fn depths(&self) -> u64 {
+        self.depths
+    }
+
+    /// How each depth compared with the one before it.
+    #[must_use]
+    pub fn trend(&self) -> Trend {
+        self.trend
+    }
+
+    /// How each sum of `WINDOW` depths compared with the one before it.
+    #[must_use]
+    pub fn window_trend(&self) -> Trend {
+        self.window_trend
+    }
+
+    /// The sum of the last `WINDOW` depths, once there have been that many.
+    #[must_use]
+    pub fn window_sum(&self) -> Option<u64> {
+        (self.depths >= WINDOW as u64).then_some(self.window_sum)
+    }
+
+    #[must_use]
+    pub fn min(&self) -> Option<u32> {
+        (self.depths > 0).then_some(self.min)
+    }
+
+    #[must_use]
+    pub fn max(&self) -> Option<u32> {
+        (self.depths > 0).then_some(self.max)
+    }
+
+    /// The most depths in a row that each got deeper, counting the one they started from.
+    #[must_use]
+    pub fn longest_increasing_run(&self) -> u64 {
+        self.longest_increasing
+    }
+
+    /// The most depths in a row that each got shallower, counting the one they started from.
+    #[must_use]
+    pub fn longest_decreasing_run(&self) -> u64 {
+        self.longest_decreasing
+    }
+}
+
+impl<const WINDOW: usize> Default for SonarAnalyzer<WINDOW> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WINDOW: usize> Extend<u32> for SonarAnalyzer<WINDOW> {
+    fn extend<I: IntoIterator<Item = u32>>(&mut self, depths: I) {
+        for depth in depths {
+            self.submit(depth);
         }
-        self.last = i;
     }
 }
 
 #[aoc(day1, part1, weird_struct_loop)]
-pub fn part1_weird_struct_loop(input: &[u32]) -> u32 {
-    let mut increase_counter = IncreaseCounter::new(input[0]);
+pub fn part1_weird_struct_loop(input: &[u32]) -> Answer {
+    let mut analyzer = SonarAnalyzer::<1>::new();
     for depth in input {
-        increase_counter.submit(*depth);
+        analyzer.submit(*depth);
     }
-    increase_counter.count
+    analyzer.trend().increases.into()
 }
 
 #[aoc(day1, part1, weird_struct_functional)]
-pub fn part1_weird_struct_functional(input: &[u32]) -> u32 {
-    let mut increase_counter = IncreaseCounter::new(input[0]);
-    input.iter().for_each(|i| increase_counter.submit(*i));
-    increase_counter.count
+pub fn part1_weird_struct_functional(input: &[u32]) -> Answer {
+    let mut analyzer = SonarAnalyzer::<1>::new();
+    analyzer.extend(input.iter().copied());
+    analyzer.trend().increases.into()
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{Comparison, SonarAnalyzer, Trend};
+    use crate::random::Rng;
+
     const TEST_DATA: [u32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
 
     #[test]
     fn part1_loop() {
         let increases = super::part1_loop(&TEST_DATA);
-        assert_eq!(increases, 7);
+        assert_eq!(increases, 7.into());
     }
 
     #[test]
     fn part1_fold() {
         let increases = super::part1_fold(&TEST_DATA);
-        assert_eq!(increases, 7);
+        assert_eq!(increases, 7.into());
+    }
+
+    #[test]
+    fn increases() {
+        let input = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+        assert_eq!(super::increases::<1>(input), 7);
+        assert_eq!(super::increases::<3>(input), 5);
+        assert_eq!(super::increases::<3>("1\n2\n3"), 0);
     }
 
     #[test]
     fn part2_loop() {
         let increases = super::part2_loop(&TEST_DATA);
-        assert_eq!(increases, 5);
+        assert_eq!(increases, 5.into());
+    }
+
+    #[test]
+    fn part2_fold() {
+        let increases = super::part2_fold(&TEST_DATA);
+        assert_eq!(increases, 5.into());
+    }
+
+    #[test]
+    fn sonar_analyzer() {
+        let mut analyzer = SonarAnalyzer::<3>::new();
+        assert_eq!((analyzer.min(), analyzer.window_sum()), (None, None));
+        analyzer.extend(TEST_DATA);
+        assert_eq!(analyzer.depths(), 10);
+        assert_eq!(
+            analyzer.trend(),
+            Trend {
+                increases: 7,
+                decreases: 2,
+                plateaus: 0
+            }
+        );
+        assert_eq!(analyzer.window_trend().increases, 5);
+        assert_eq!(analyzer.window_sum(), Some(269 + 260 + 263));
+        assert_eq!((analyzer.min(), analyzer.max()), (Some(199), Some(269)));
+        assert_eq!(analyzer.longest_increasing_run(), 4);
+        assert_eq!(analyzer.longest_decreasing_run(), 2);
+
+        let mut analyzer = SonarAnalyzer::<1>::new();
+        analyzer.extend([5, 5, 4, 3, 3, 2, 1, 0, 0]);
+        assert_eq!(analyzer.trend().plateaus, 3);
+        assert_eq!(analyzer.longest_increasing_run(), 1);
+        assert_eq!(analyzer.longest_decreasing_run(), 4);
+    }
+
+    // Compares the window trend for windows of `WINDOW` depths with `count_windows`.
+    fn check_window_trend<const WINDOW: usize>(depths: &[u32]) {
+        let mut analyzer = SonarAnalyzer::<WINDOW>::new();
+        analyzer.extend(depths.iter().copied());
+        let trend = analyzer.window_trend();
+        let count = |comparison| super::count_windows(depths, WINDOW, comparison) as u64;
+        assert_eq!(trend.increases, count(Comparison::Greater), "{depths:?}");
+        assert_eq!(trend.decreases, count(Comparison::Less), "{depths:?}");
+        let plateaus = count(Comparison::GreaterOrEqual) - trend.increases;
+        assert_eq!(trend.plateaus, plateaus, "{depths:?}");
+        let expected_sum = depths
+            .len()
+            .checked_sub(WINDOW)
+            .map(|start| depths[start..].iter().copied().map(u64::from).sum());
+        assert_eq!(analyzer.window_sum(), expected_sum, "{depths:?}");
+    }
+
+    #[test]
+    fn window_trend() {
+        let mut rng = Rng::new(50);
+        for len in 0..30 {
+            let depths: Vec<u32> = (0..len)
+                .map(|_| u32::try_from(rng.range(0..=4)).unwrap())
+                .collect();
+            check_window_trend::<1>(&depths);
+            check_window_trend::<2>(&depths);
+            check_window_trend::<3>(&depths);
+            check_window_trend::<7>(&depths);
+        }
+    }
+
+    #[test]
+    fn weird_struct() {
+        assert_eq!(super::part1_weird_struct_loop(&TEST_DATA), 7.into());
+        assert_eq!(super::part1_weird_struct_functional(&TEST_DATA), 7.into());
+    }
+
+    // Sums every window, the way the puzzle describes it.
+    fn count_windows_by_summing(items: &[i64], window: usize, comparison: Comparison) -> usize {
+        let sums: Vec<i64> = items.windows(window).map(|w| w.iter().sum()).collect();
+        sums.windows(2)
+            .filter(|pair| comparison.holds(&pair[1], &pair[0]))
+            .count()
+    }
+
+    #[test]
+    fn count_windows() {
+        let mut rng = Rng::new(49);
+        for len in 0..40 {
+            // A small range so equal neighbours are common.
+            let items: Vec<i64> = (0..len)
+                .map(|_| i64::try_from(rng.range(0..=6)).unwrap() - 3)
+                .collect();
+            for window in 1..=len + 1 {
+                for comparison in [
+                    Comparison::Greater,
+                    Comparison::GreaterOrEqual,
+                    Comparison::Less,
+                ] {
+                    assert_eq!(
+                        super::count_windows(&items, window, comparison),
+                        count_windows_by_summing(&items, window, comparison),
+                        "{items:?} in windows of {window}, {comparison:?}"
+                    );
+                }
+            }
+        }
+
+        let floats = [0.5, 1.5, 1.0, 1.0, 2.5];
+        assert_eq!(super::count_windows(&floats, 1, Comparison::Greater), 2);
+        assert_eq!(super::count_windows(&floats, 2, Comparison::Less), 1);
+        assert_eq!(
+            super::count_windows(&floats, 2, Comparison::GreaterOrEqual),
+            2
+        );
     }
 }
@@ -1,6 +1,13 @@
+use crate::answer::Answer;
+use crate::cache::Encode;
+#[cfg(feature = "viz")]
+use crate::viz::{self, Canvas, Colour, Frame};
+use std::fmt;
 use std::str::FromStr;
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Direction {
     Forward,
     Down,
@@ -8,18 +15,26 @@ pub enum Direction {
 }
 
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Command {
     direction: Direction,
     value: u64,
 }
 
+#[cfg(feature = "viz")]
+const VIZ_WIDTH: usize = 72;
+#[cfg(feature = "viz")]
+const VIZ_HEIGHT: usize = 20;
+#[cfg(feature = "viz")]
+const VIZ_FRAMES: usize = 60;
+
 #[aoc_generator(day2)]
 pub fn input_generator(input: &str) -> Vec<Command> {
     input.lines().map(|l| l.parse().unwrap()).collect()
 }
 
 #[aoc(day2, part1)]
-pub fn part1(input: &[Command]) -> u64 {
+pub fn part1(input: &[Command]) -> Answer {
     let mut depth = 0;
     let mut horiz = 0;
     for cmd in input {
@@ -30,11 +45,11 @@ pub fn part1(input: &[Command]) -> u64 {
             Direction::Up => depth -= value,
         }
     }
-    depth * horiz
+    Answer::from(depth * horiz)
 }
 
 #[aoc(day2, part2)]
-pub fn part2(input: &[Command]) -> u64 {
+pub fn part2(input: &[Command]) -> Answer {
     let mut aim = 0;
     let mut depth = 0;
     let mut horiz = 0;
@@ -51,7 +66,46 @@ pub fn part2(input: &[Command]) -> u64 {
         }
     }
 
-    depth * horiz
+    Answer::from(depth * horiz)
+}
+
+/// Plots the course from part 2, where up and down turn the submarine rather than move it.
+#[cfg(feature = "viz")]
+pub(crate) fn frames(commands: &[Command]) -> Vec<Frame> {
+    let mut path = vec![(0_u64, 0_u64)];
+    let mut aim = 0;
+    for Command { direction, value } in commands {
+        let (horiz, depth) = path[path.len() - 1];
+        match direction {
+            Direction::Forward => path.push((horiz + value, depth + value * aim)),
+            Direction::Down => aim += value,
+            Direction::Up => aim -= value,
+        }
+    }
+    let furthest = path.iter().map(|p| p.0).max().unwrap_or_default();
+    let deepest = path.iter().map(|p| p.1).max().unwrap_or_default();
+    let step = path.len().div_ceil(VIZ_FRAMES);
+
+    (1..=path.len())
+        .filter(|&end| end % step == 0 || end == path.len())
+        .map(|end| {
+            let mut canvas = Canvas::new(VIZ_WIDTH, VIZ_HEIGHT, ' ');
+            for (i, &(horiz, depth)) in path[..end].iter().enumerate() {
+                let x = viz::scale(horiz, furthest, VIZ_WIDTH);
+                let y = viz::scale(depth, deepest, VIZ_HEIGHT);
+                if i + 1 == end {
+                    canvas.set(x, y, 'S', Colour::Yellow);
+                } else if canvas.get(x, y) == ' ' {
+                    canvas.set(x, y, '.', Colour::Blue);
+                }
+            }
+            let (horiz, depth) = path[end - 1];
+            let mut frame = Frame::new();
+            frame.push(format!("horizontal {horiz}, depth {depth}"), Colour::Plain);
+            canvas.draw(&mut frame);
+            frame
+        })
+        .collect()
 }
 
 impl FromStr for Direction {
@@ -62,7 +116,7 @@ impl FromStr for Direction {
             "forward" => Ok(Direction::Forward),
             "down" => Ok(Direction::Down),
             "up" => Ok(Direction::Up),
-            _ => Err(format!("unknown direction: {}", s)),
+            _ => Err(format!("unknown direction: {s}")),
         }
     }
 }
@@ -78,9 +132,60 @@ impl FromStr for Command {
     }
 }
 
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Direction::Forward => "forward",
+            Direction::Down => "down",
+            Direction::Up => "up",
+        })
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.direction, self.value)
+    }
+}
+
+impl Encode for Direction {
+    fn encode(&self, out: &mut Vec<u8>) {
+        let tag: u8 = match self {
+            Direction::Forward => 0,
+            Direction::Down => 1,
+            Direction::Up => 2,
+        };
+        tag.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        match u8::decode(input)? {
+            0 => Some(Direction::Forward),
+            1 => Some(Direction::Down),
+            2 => Some(Direction::Up),
+            _ => None,
+        }
+    }
+}
+
+impl Encode for Command {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.direction.encode(out);
+        self.value.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(Command {
+            direction: Direction::decode(input)?,
+            value: u64::decode(input)?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{Command, Direction};
+    use crate::random::Rng;
 
     const COMMANDS: [Command; 6] = [
         Command {
@@ -122,15 +227,60 @@ forward 2",
         assert_eq!(commands, COMMANDS);
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let json = serde_json::to_string(&COMMANDS[..2]).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"direction":"forward","value":5},{"direction":"down","value":5}]"#
+        );
+        let commands: Vec<Command> = serde_json::from_str(&json).unwrap();
+        assert_eq!(commands, COMMANDS[..2]);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(COMMANDS[0].to_string(), "forward 5");
+        assert_eq!(COMMANDS[3].to_string(), "up 3");
+
+        let mut rng = Rng::new(2);
+        for _ in 0..100 {
+            let direction = match rng.range(0..=2) {
+                0 => Direction::Forward,
+                1 => Direction::Down,
+                _ => Direction::Up,
+            };
+            let command = Command {
+                direction,
+                value: rng.next_u64(),
+            };
+            assert_eq!(command.to_string().parse(), Ok(command));
+        }
+    }
+
     #[test]
     fn part1() {
         let result = super::part1(&COMMANDS);
-        assert_eq!(result, 150);
+        assert_eq!(result, 150.into());
     }
 
     #[test]
     fn part2() {
         let result = super::part2(&COMMANDS);
-        assert_eq!(result, 900);
+        assert_eq!(result, 900.into());
+    }
+
+    #[test]
+    #[cfg(feature = "viz")]
+    fn frames() {
+        let frames = super::frames(&COMMANDS);
+        assert_eq!(frames.len(), 4);
+        let last = frames[3].text();
+        assert!(last.starts_with("horizontal 15, depth 60\n"));
+        assert_eq!(
+            last.lines().last().unwrap().trim_end().chars().last(),
+            Some('S')
+        );
     }
 }
@@ -1,3 +1,8 @@
+use crate::answer::Answer;
+use crate::cancel::{self, Token};
+use crate::repl::Explore;
+use crate::trace::event;
+
 #[aoc_generator(day3)]
 pub fn input_generator(input: &str) -> Vec<Vec<bool>> {
     input
@@ -15,8 +20,8 @@ pub fn input_generator(input: &str) -> Vec<Vec<bool>> {
 }
 
 #[aoc(day3, part1)]
-pub fn part1(input: &[Vec<bool>]) -> u64 {
-    let mut bit_counts = vec![0; input.get(0).unwrap().len()];
+pub fn part1(input: &[Vec<bool>]) -> Answer {
+    let mut bit_counts = vec![0; input.first().unwrap().len()];
     for line in input {
         for (pos, &bit) in line.iter().enumerate() {
             if bit {
@@ -37,19 +42,20 @@ pub fn part1(input: &[Vec<bool>]) -> u64 {
         (gamma, eps)
     });
 
-    gamma * eps
+    Answer::from(gamma * eps)
 }
 
 #[aoc(day3, part2)]
-pub fn part2(input: &[Vec<bool>]) -> u64 {
-    let o2_generator = winnow(input, true)
+pub fn part2(input: &[Vec<bool>]) -> Answer {
+    let cancel = cancel::current();
+    let o2_generator = winnow(input, true, cancel.as_ref())
         .iter()
         .fold(0_u64, |i, &b| if b { i << 1 | 1 } else { i << 1 });
-    let co2_scrubber = winnow(input, false)
+    let co2_scrubber = winnow(input, false, cancel.as_ref())
         .iter()
         .fold(0_u64, |i, &b| if b { i << 1 | 1 } else { i << 1 });
 
-    o2_generator * co2_scrubber
+    (o2_generator * co2_scrubber).into()
 }
 
 fn count_bits_at_pos(input: &[Vec<bool>], pos: usize) -> i32 {
@@ -58,25 +64,142 @@ fn count_bits_at_pos(input: &[Vec<bool>], pos: usize) -> i32 {
     })
 }
 
-fn winnow(input: &[Vec<bool>], most_common: bool) -> Vec<bool> {
-    let len = input.get(0).unwrap().len();
+fn winnow(input: &[Vec<bool>], most_common: bool, cancel: Option<&Token>) -> Vec<bool> {
+    let len = input.first().unwrap().len();
     let mut input = input.to_vec();
     let mut pos = 0;
-    let mut want;
     while input.len() != 1 {
-        let bit_count = count_bits_at_pos(&input, pos);
-        want = if most_common {
-            bit_count >= 0
-        } else {
-            bit_count < 0
-        };
-        input = input
-            .into_iter()
-            .filter(|i| *i.get(pos).unwrap() == want)
-            .collect();
+        cancel::check(cancel);
+        winnow_step(&mut input, pos, most_common);
         pos = (pos + 1) % len;
     }
-    input.get(0).unwrap().clone()
+    input.first().unwrap().clone()
+}
+
+/// Keeps the rows with the most or least common bit at `pos`, returning that bit.
+fn winnow_step(input: &mut Vec<Vec<bool>>, pos: usize, most_common: bool) -> bool {
+    let bit_count = count_bits_at_pos(input, pos);
+    let want = if most_common {
+        bit_count >= 0
+    } else {
+        bit_count < 0
+    };
+    input.retain(|i| *i.get(pos).unwrap() == want);
+    event!(
+        "winnow_round",
+        pos = pos,
+        want = want,
+        remaining = input.len()
+    );
+    want
+}
+
+/// Winnows the rows a position at a time for `aoc repl`.
+pub(crate) struct Explorer {
+    rows: Vec<Vec<bool>>,
+    winnowing: Option<Winnowing>,
+}
+
+struct Winnowing {
+    most_common: bool,
+    remaining: Vec<Vec<bool>>,
+    pos: usize,
+}
+
+impl Explorer {
+    pub(crate) fn new(rows: Vec<Vec<bool>>) -> Self {
+        Self {
+            rows,
+            winnowing: None,
+        }
+    }
+}
+
+impl Explore for Explorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            (
+                "rows",
+                "how many rows there are and the bit counts at each position",
+            ),
+            (
+                "winnow o2|co2",
+                "starts winnowing for the oxygen or CO2 rating",
+            ),
+            (
+                "winnow step",
+                "keeps the rows that match at the next position",
+            ),
+            ("winnow", "the rows left"),
+        ]
+    }
+
+    fn run(&mut self, words: &[&str]) -> Option<Result<String, String>> {
+        let not_started = || "start with `winnow o2` or `winnow co2`".to_string();
+        Some(match *words {
+            ["rows"] => width(&self.rows).map(|width| {
+                let counts: Vec<_> = (0..width)
+                    .map(|pos| format!("{:+}", count_bits_at_pos(&self.rows, pos)))
+                    .collect();
+                format!(
+                    "{} rows, ones minus zeros at each position: {}",
+                    self.rows.len(),
+                    counts.join(" ")
+                )
+            }),
+            ["winnow", rating @ ("o2" | "co2")] => {
+                self.winnowing = Some(Winnowing {
+                    most_common: rating == "o2",
+                    remaining: self.rows.clone(),
+                    pos: 0,
+                });
+                Ok(format!("{} rows to winnow", self.rows.len()))
+            }
+            ["winnow", "step"] => self
+                .winnowing
+                .as_mut()
+                .ok_or_else(not_started)
+                .and_then(|w| {
+                    let width = width(&w.remaining)?;
+                    if w.remaining.len() <= 1 || width == 0 {
+                        return Err("there's nothing left to winnow".to_string());
+                    }
+                    let want = winnow_step(&mut w.remaining, w.pos, w.most_common);
+                    let pos = w.pos;
+                    w.pos = (w.pos + 1) % width;
+                    Ok(format!(
+                        "kept {} rows with a {} at position {pos}",
+                        w.remaining.len(),
+                        u8::from(want)
+                    ))
+                }),
+            ["winnow"] => self.winnowing.as_ref().ok_or_else(not_started).map(|w| {
+                w.remaining
+                    .iter()
+                    .map(|row| bits(row))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }),
+            _ => return None,
+        })
+    }
+}
+
+// The rows' shared width, which winnowing needs since it looks at every row's bit at a position.
+fn width(rows: &[Vec<bool>]) -> Result<usize, String> {
+    let width = rows.first().map_or(0, Vec::len);
+    match rows.iter().position(|row| row.len() != width) {
+        Some(i) => Err(format!(
+            "row {} has {} bits but row 1 has {width}",
+            i + 1,
+            rows[i].len()
+        )),
+        None => Ok(width),
+    }
+}
+
+fn bits(row: &[bool]) -> String {
+    row.iter().map(|&b| if b { '1' } else { '0' }).collect()
 }
 
 // #[aoc(day3, part2)]
@@ -86,6 +209,8 @@ fn winnow(input: &[Vec<bool>], most_common: bool) -> Vec<bool> {
 
 #[cfg(test)]
 mod tests {
+    use crate::cancel::{Cancelled, Token};
+
     const INPUT: &str = "00100
 11110
 10110
@@ -114,7 +239,7 @@ mod tests {
     #[test]
     fn part1() {
         let input = super::input_generator(INPUT);
-        assert_eq!(super::part1(&input), 198);
+        assert_eq!(super::part1(&input), 198.into());
     }
 
     #[test]
@@ -127,19 +252,41 @@ mod tests {
     fn winnow() {
         // most common bit
         let input = super::input_generator(INPUT);
-        assert_eq!(super::winnow(&input, true), [true, false, true, true, true]);
+        assert_eq!(
+            super::winnow(&input, true, None),
+            [true, false, true, true, true]
+        );
 
         // least common bit
         let input = super::input_generator(INPUT);
         assert_eq!(
-            super::winnow(&input, false),
+            super::winnow(&input, false, None),
             [false, true, false, true, false]
         );
     }
 
+    #[test]
+    fn winnow_step() {
+        let mut input = super::input_generator(INPUT);
+        assert!(super::winnow_step(&mut input, 0, true));
+        assert_eq!(input.len(), 7);
+        assert!(!super::winnow_step(&mut input, 1, true));
+        assert_eq!(input.len(), 4);
+    }
+
+    #[test]
+    fn winnow_cancelled() {
+        // Duplicate rows would otherwise keep this going forever.
+        let input = super::input_generator("101\n101");
+        let token = Token::new();
+        token.cancel();
+        let result = std::panic::catch_unwind(|| super::winnow(&input, true, Some(&token)));
+        assert!(result.unwrap_err().is::<Cancelled>());
+    }
+
     #[test]
     fn part2() {
         let input = super::input_generator(INPUT);
-        assert_eq!(super::part2(&input), 230);
+        assert_eq!(super::part2(&input), 230.into());
     }
 }
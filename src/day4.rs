@@ -1,15 +1,28 @@
+use crate::answer::Answer;
+use crate::cache::Encode;
+use crate::repl::{self, Explore};
+use crate::trace::event;
+#[cfg(feature = "viz")]
+use crate::viz::{Colour, Frame};
+use std::fmt;
+
 #[derive(Default, Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Square {
     val: u8,
     marked: bool,
 }
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Board {
     squares: [[Square; 5]; 5],
     has_won: bool,
 }
 
+#[cfg(feature = "viz")]
+const VIZ_BOARDS_PER_ROW: usize = 5;
+
 #[aoc_generator(day4)]
 pub fn input_generator(input: &str) -> (Vec<u8>, Vec<Board>) {
     let mut input = input.split("\n\n");
@@ -29,13 +42,15 @@ pub fn input_generator(input: &str) -> (Vec<u8>, Vec<Board>) {
 }
 
 #[aoc(day4, part1)]
-pub fn part1((moves, boards): &(Vec<u8>, Vec<Board>)) -> u64 {
+pub fn part1((moves, boards): &(Vec<u8>, Vec<Board>)) -> Answer {
     let mut boards = boards.clone();
 
     for drawn in moves {
-        for board in &mut boards {
+        for (i, board) in boards.iter_mut().enumerate() {
             if board.mark_and_check_win(*drawn) {
-                return board.sum_unmarked() * u64::from(*drawn);
+                let score = board.sum_unmarked() * u64::from(*drawn);
+                event!("bingo_win", board = i, drawn = drawn, score = score);
+                return score.into();
             }
         }
     }
@@ -43,22 +58,181 @@ pub fn part1((moves, boards): &(Vec<u8>, Vec<Board>)) -> u64 {
 }
 
 #[aoc(day4, part2)]
-pub fn part2((moves, boards): &(Vec<u8>, Vec<Board>)) -> u64 {
+pub fn part2((moves, boards): &(Vec<u8>, Vec<Board>)) -> Answer {
     let mut boards = boards.clone();
     let mut last_won_score = 0_u64;
 
     for drawn in moves {
-        for board in &mut boards {
+        for (i, board) in boards.iter_mut().enumerate() {
             if board.has_won {
                 continue;
             }
             if board.mark_and_check_win(*drawn) {
                 last_won_score = board.sum_unmarked() * u64::from(*drawn);
+                event!(
+                    "bingo_win",
+                    board = i,
+                    drawn = drawn,
+                    score = last_won_score
+                );
             }
         }
     }
 
-    last_won_score
+    last_won_score.into()
+}
+
+/// One frame per number drawn until every board has won, with marked squares highlighted.
+#[cfg(feature = "viz")]
+pub(crate) fn frames((moves, boards): &(Vec<u8>, Vec<Board>)) -> Vec<Frame> {
+    let mut boards = boards.clone();
+    let mut frames = vec![];
+    for (i, &drawn) in moves.iter().enumerate() {
+        for board in boards.iter_mut().filter(|b| !b.has_won) {
+            board.mark_and_check_win(drawn);
+        }
+        let won = boards.iter().filter(|b| b.has_won).count();
+
+        let mut frame = Frame::new();
+        frame.push(
+            format!(
+                "Drew {drawn} ({} of {}), {won} of {} boards won",
+                i + 1,
+                moves.len(),
+                boards.len()
+            ),
+            Colour::Plain,
+        );
+        for (row_of_boards, chunk) in boards.chunks(VIZ_BOARDS_PER_ROW).enumerate() {
+            frame.newline().newline();
+            for (j, board) in chunk.iter().enumerate() {
+                let label = format!("Board {}", row_of_boards * VIZ_BOARDS_PER_ROW + j + 1);
+                let colour = if board.has_won {
+                    Colour::Green
+                } else {
+                    Colour::Plain
+                };
+                frame.push(format!("{label:<17}"), colour);
+            }
+            for row in 0..5 {
+                frame.newline();
+                for board in chunk {
+                    for square in board.squares[row] {
+                        let colour = match square {
+                            Square { marked: false, .. } => Colour::Dim,
+                            Square { val, .. } if val == drawn => Colour::Yellow,
+                            _ => Colour::Green,
+                        };
+                        frame.push(format!("{:>2} ", square.val), colour);
+                    }
+                    frame.push("  ", Colour::Plain);
+                }
+            }
+        }
+        frames.push(frame);
+
+        if won == boards.len() {
+            break;
+        }
+    }
+    frames
+}
+
+/// Plays bingo a number at a time for `aoc repl`.
+pub(crate) struct Explorer {
+    calls: Vec<u8>,
+    boards: Vec<Board>,
+    next: usize,
+}
+
+impl Explorer {
+    pub(crate) fn new((calls, boards): (Vec<u8>, Vec<Board>)) -> Self {
+        Self {
+            calls,
+            boards,
+            next: 0,
+        }
+    }
+
+    fn draw(&mut self, drawn: u8) -> String {
+        let mut lines = vec![format!("drew {drawn}")];
+        for (i, board) in self.boards.iter_mut().enumerate() {
+            if !board.has_won && board.mark_and_check_win(drawn) {
+                let score = board.sum_unmarked() * u64::from(drawn);
+                lines.push(format!("board {} wins with a score of {score}", i + 1));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl Explore for Explorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("boards", "how many squares of each board are marked"),
+            ("draw [N]", "marks the next number called, or N out of turn"),
+            (
+                "show board N",
+                "board N with its marked squares in brackets",
+            ),
+            ("calls", "the numbers called so far"),
+        ]
+    }
+
+    fn run(&mut self, words: &[&str]) -> Option<Result<String, String>> {
+        Some(match *words {
+            ["boards"] => Ok(self
+                .boards
+                .iter()
+                .enumerate()
+                .map(|(i, board)| {
+                    let marked = board.squares.iter().flatten().filter(|s| s.marked).count();
+                    let won = if board.has_won { ", won" } else { "" };
+                    format!("board {}: {marked} marked{won}", i + 1)
+                })
+                .collect::<Vec<_>>()
+                .join("\n")),
+            ["draw"] => match self.calls.get(self.next) {
+                Some(&drawn) => {
+                    self.next += 1;
+                    Ok(self.draw(drawn))
+                }
+                None => Err("every number has been called".to_string()),
+            },
+            ["draw", n] => repl::number(n, "a bingo number").map(|n| self.draw(n)),
+            ["show", "board", n] => repl::number::<usize>(n, "a board number").and_then(|n| {
+                let board = n
+                    .checked_sub(1)
+                    .and_then(|i| self.boards.get(i))
+                    .ok_or_else(|| format!("there are {} boards", self.boards.len()))?;
+                let rows = board.squares.iter().map(|row| {
+                    row.iter()
+                        .map(|s| {
+                            if s.marked {
+                                format!("[{:>2}]", s.val)
+                            } else {
+                                format!(" {:>2} ", s.val)
+                            }
+                        })
+                        .collect::<String>()
+                        .trim_end()
+                        .to_string()
+                });
+                let mut lines: Vec<_> = rows.collect();
+                lines.push(format!("unmarked: {}", board.sum_unmarked()));
+                Ok(lines.join("\n"))
+            }),
+            ["calls"] => {
+                let (called, left) = self.calls.split_at(self.next);
+                Ok(format!(
+                    "called {}: {called:?}\n{} left",
+                    called.len(),
+                    left.len()
+                ))
+            }
+            _ => return None,
+        })
+    }
 }
 
 impl Board {
@@ -89,7 +263,7 @@ impl Board {
 
         for row in 0..squares.len() {
             for col in 0..squares[row].len() {
-                let mut square = &mut squares[row][col];
+                let square = &mut squares[row][col];
                 if square.val == val {
                     square.marked = true;
                     if (0..squares.len()).all(|row| squares[row][col].marked)
@@ -120,8 +294,56 @@ impl Square {
     }
 }
 
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.squares.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for (j, square) in row.iter().enumerate() {
+                if j > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "{:>2}", square.val)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Encode for Square {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.val.encode(out);
+        self.marked.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(Self {
+            val: u8::decode(input)?,
+            marked: bool::decode(input)?,
+        })
+    }
+}
+
+impl Encode for Board {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.squares.encode(out);
+        self.has_won.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(Self {
+            squares: Encode::decode(input)?,
+            has_won: bool::decode(input)?,
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::{Board, Square};
+    use crate::random::Rng;
+
     const RAW_INPUT: &str = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
 
 22 13 17 11  0
@@ -193,15 +415,62 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let (_, mut boards) = super::input_generator(RAW_INPUT);
+        boards[0].mark_and_check_win(22);
+
+        let json = serde_json::to_string(&boards).unwrap();
+        assert!(
+            json.starts_with(r#"[{"squares":[[{"val":22,"marked":true},{"val":13,"marked":false}"#)
+        );
+        let round_tripped: Vec<super::Board> = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped, boards);
+    }
+
+    #[test]
+    fn display() {
+        let (_, boards) = super::input_generator(RAW_INPUT);
+        let rendered: Vec<_> = boards.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            rendered.join("\n\n"),
+            RAW_INPUT.split_once("\n\n").unwrap().1
+        );
+
+        let mut rng = Rng::new(4);
+        let mut values: Vec<u8> = (0..=99).collect();
+        for _ in 0..100 {
+            rng.shuffle(&mut values);
+            let mut board = Board::default();
+            for (square, &val) in board.squares.iter_mut().flatten().zip(&values) {
+                *square = Square::new(val);
+            }
+            assert_eq!(Board::parse(board.to_string()), board);
+        }
+    }
+
     #[test]
     fn part1() {
         let (moves, boards) = super::input_generator(RAW_INPUT);
-        assert_eq!(super::part1(&(moves, boards)), 4512);
+        assert_eq!(super::part1(&(moves, boards)), 4512.into());
     }
 
     #[test]
     fn part2() {
         let (moves, boards) = super::input_generator(RAW_INPUT);
-        assert_eq!(super::part2(&(moves, boards)), 1924);
+        assert_eq!(super::part2(&(moves, boards)), 1924.into());
+    }
+
+    #[test]
+    #[cfg(feature = "viz")]
+    fn frames() {
+        let frames = super::frames(&super::input_generator(RAW_INPUT));
+        assert_eq!(frames.len(), 15);
+        let first = frames[0].text();
+        assert!(first.starts_with("Drew 7 (1 of 27), 0 of 3 boards won\n\nBoard 1"));
+        assert!(frames[14]
+            .text()
+            .starts_with("Drew 13 (15 of 27), 3 of 3 boards won\n"));
     }
 }
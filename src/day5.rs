@@ -1,17 +1,32 @@
+use crate::answer::Answer;
+use crate::cache::Encode;
+use crate::cancel;
+use crate::progress;
+#[cfg(feature = "viz")]
+use crate::viz::{self, Canvas, Colour, Frame};
 use std::collections::HashMap;
+use std::fmt;
 
 #[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     x: u16,
     y: u16,
 }
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Line {
     start: Point,
     end: Point,
 }
 
+// Bigger maps are scaled down to this many cells a side.
+#[cfg(feature = "viz")]
+const VIZ_SIZE: usize = 60;
+#[cfg(feature = "viz")]
+const VIZ_FRAMES: usize = 60;
+
 #[aoc_generator(day5)]
 pub fn input_generator(input: &str) -> Vec<Line> {
     input
@@ -31,10 +46,16 @@ pub fn input_generator(input: &str) -> Vec<Line> {
 }
 
 #[aoc(day5, part1)]
-pub fn part1(lines: &[Line]) -> u64 {
+pub fn part1(lines: &[Line]) -> Answer {
     let mut lines_at_point = HashMap::new();
+    let cancel = cancel::current();
+    let mut progress = progress::start("lines", lines.len() as u64);
     lines
         .iter()
+        .inspect(|_| {
+            cancel::check(cancel.as_ref());
+            progress.advance(1);
+        })
         .flat_map(|line| line.all_points_on_line(false))
         .for_each(|point| {
             lines_at_point
@@ -46,15 +67,20 @@ pub fn part1(lines: &[Line]) -> u64 {
         .iter()
         .filter(|(_, &count)| count >= 2)
         .count()
-        .try_into()
-        .unwrap()
+        .into()
 }
 
 #[aoc(day5, part2)]
-pub fn part2(lines: &[Line]) -> u64 {
+pub fn part2(lines: &[Line]) -> Answer {
     let mut lines_at_point = HashMap::new();
+    let cancel = cancel::current();
+    let mut progress = progress::start("lines", lines.len() as u64);
     lines
         .iter()
+        .inspect(|_| {
+            cancel::check(cancel.as_ref());
+            progress.advance(1);
+        })
         .flat_map(|line| line.all_points_on_line(true))
         .for_each(|point| {
             lines_at_point
@@ -66,8 +92,54 @@ pub fn part2(lines: &[Line]) -> u64 {
         .iter()
         .filter(|(_, &count)| count >= 2)
         .count()
-        .try_into()
-        .unwrap()
+        .into()
+}
+
+/// How many lines, diagonals included, cover each point.
+pub(crate) fn coverage(lines: &[Line]) -> HashMap<(u16, u16), u16> {
+    let mut counts = HashMap::new();
+    for Point { x, y } in lines.iter().flat_map(|line| line.all_points_on_line(true)) {
+        *counts.entry((x, y)).or_default() += 1;
+    }
+    counts
+}
+
+/// Builds the vent map up a few lines at a time, diagonals included.
+#[cfg(feature = "viz")]
+pub(crate) fn frames(lines: &[Line]) -> Vec<Frame> {
+    let max = lines
+        .iter()
+        .flat_map(|l| [l.start.x, l.start.y, l.end.x, l.end.y])
+        .max()
+        .unwrap_or_default();
+    let size = (usize::from(max) + 1).min(VIZ_SIZE);
+    let cell = |v: u16| viz::scale(u64::from(v), u64::from(max), size);
+
+    let mut counts = vec![0_u32; size * size];
+    let mut drawn = 0;
+    let mut frames = vec![];
+    for chunk in lines.chunks(lines.len().div_ceil(VIZ_FRAMES).max(1)) {
+        for point in chunk.iter().flat_map(|l| l.all_points_on_line(true)) {
+            counts[cell(point.y) * size + cell(point.x)] += 1;
+        }
+        drawn += chunk.len();
+
+        let mut canvas = Canvas::new(size, size, '.');
+        for (i, &count) in counts.iter().enumerate().filter(|(_, &c)| c > 0) {
+            let c = char::from_digit(count, 10).unwrap_or('#');
+            let colour = if count == 1 {
+                Colour::Blue
+            } else {
+                Colour::Red
+            };
+            canvas.set(i % size, i / size, c, colour);
+        }
+        let mut frame = Frame::new();
+        frame.push(format!("{drawn} of {} lines", lines.len()), Colour::Plain);
+        canvas.draw(&mut frame);
+        frames.push(frame);
+    }
+    frames
 }
 
 impl Point {
@@ -101,6 +173,40 @@ impl Line {
     }
 }
 
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -> {}", self.start, self.end)
+    }
+}
+
+impl Encode for Point {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.x.encode(out);
+        self.y.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(Self::new(u16::decode(input)?, u16::decode(input)?))
+    }
+}
+
+impl Encode for Line {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.start.encode(out);
+        self.end.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Some(Self::new(Point::decode(input)?, Point::decode(input)?))
+    }
+}
+
 fn range(start: u16, end: u16) -> Box<dyn Iterator<Item = u16>> {
     if start < end {
         Box::new(start..=end)
@@ -112,6 +218,7 @@ fn range(start: u16, end: u16) -> Box<dyn Iterator<Item = u16>> {
 #[cfg(test)]
 mod tests {
     use super::{Line, Point};
+    use crate::random::Rng;
 
     const RAW_INPUT: &str = "0,9 -> 5,9
 8,0 -> 0,8
@@ -143,15 +250,80 @@ mod tests {
         assert_eq!(lines, LINES);
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let json = serde_json::to_string(&LINES[0]).unwrap();
+        assert_eq!(json, r#"{"start":{"x":0,"y":9},"end":{"x":5,"y":9}}"#);
+        let lines: Vec<Line> =
+            serde_json::from_str(&serde_json::to_string(&LINES).unwrap()).unwrap();
+        assert_eq!(lines, LINES);
+    }
+
+    #[test]
+    fn display() {
+        let rendered: Vec<_> = LINES.iter().map(ToString::to_string).collect();
+        assert_eq!(rendered.join("\n"), RAW_INPUT);
+
+        let mut rng = Rng::new(5);
+        let mut coord = || u16::try_from(rng.range(0..=u64::from(u16::MAX))).unwrap();
+        for _ in 0..100 {
+            let line = Line::new(Point::new(coord(), coord()), Point::new(coord(), coord()));
+            assert_eq!(super::input_generator(&line.to_string()), [line]);
+        }
+    }
+
     #[test]
     fn part1() {
         let lines = super::input_generator(RAW_INPUT);
-        assert_eq!(super::part1(&lines), 5);
+        assert_eq!(super::part1(&lines), 5.into());
     }
 
     #[test]
     fn part2() {
         let lines = super::input_generator(RAW_INPUT);
-        assert_eq!(super::part2(&lines), 12);
+        assert_eq!(super::part2(&lines), 12.into());
+    }
+
+    #[test]
+    fn cancelled() {
+        use crate::cancel::{self, Cancelled, Token};
+
+        let lines = super::input_generator(RAW_INPUT);
+        let token = Token::new();
+        token.cancel();
+        cancel::set_current(Some(token));
+        let result = std::panic::catch_unwind(|| super::part2(&lines));
+        cancel::set_current(None);
+        assert!(result.unwrap_err().is::<Cancelled>());
+    }
+
+    #[test]
+    fn coverage() {
+        let counts = super::coverage(&LINES);
+        assert_eq!(counts.values().filter(|&&c| c >= 2).count(), 12);
+        assert_eq!(counts[&(4, 4)], 3);
+    }
+
+    #[test]
+    #[cfg(feature = "viz")]
+    fn frames() {
+        let frames = super::frames(&LINES);
+        assert_eq!(frames.len(), 10);
+        assert_eq!(
+            frames[9].text(),
+            "10 of 10 lines
+1.1....11.
+.111...2..
+..2.1.111.
+...1.2.2..
+.112313211
+...1.2....
+..1...1...
+.1.....1..
+1.......1.
+222111....
+"
+        );
     }
 }
@@ -1,7 +1,26 @@
-// #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+use crate::answer::Answer;
+use crate::cache::Encode;
+use crate::cancel;
+use crate::comptime;
+use crate::progress;
+use crate::repl::{self, Explore};
+use crate::trace::event;
+#[cfg(feature = "viz")]
+use crate::viz::{self, Colour, Frame};
+use std::fmt;
+
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
 #[derive(Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Fish([u64; 9]);
 
+// How many simulated days go by between trace events.
+const TRACE_BATCH: u16 = 32;
+#[cfg(feature = "viz")]
+const VIZ_DAYS: u16 = 80;
+#[cfg(feature = "viz")]
+const VIZ_BAR_WIDTH: usize = 50;
+
 #[aoc_generator(day6)]
 pub fn input_generator(input: &str) -> Fish {
     let mut fish = Fish::default();
@@ -13,51 +32,321 @@ pub fn input_generator(input: &str) -> Fish {
 }
 
 #[aoc(day6, part1)]
-pub fn part1(fish: &Fish) -> u64 {
+pub fn part1(fish: &Fish) -> Answer {
     let mut fish = fish.clone();
     fish.advance_days(80);
-    fish.count()
+    fish.count().into()
 }
 
 #[aoc(day6, part2)]
-pub fn part2(fish: &Fish) -> u64 {
+pub fn part2(fish: &Fish) -> Answer {
     let mut fish = fish.clone();
     fish.advance_days(256);
-    fish.count()
+    fish.count().into()
+}
+
+/// How many fish there are at the end of each of the next `num_days` days.
+pub(crate) fn growth(fish: &Fish, num_days: u16) -> Vec<u64> {
+    let mut fish = fish.clone();
+    (0..num_days)
+        .map(|_| {
+            fish.step();
+            fish.count()
+        })
+        .collect()
+}
+
+/// One frame per day for the first 80 days, with each age bucket drawn as a bar.
+#[cfg(feature = "viz")]
+pub(crate) fn frames(fish: &Fish) -> Vec<Frame> {
+    let mut fish = fish.clone();
+    let mut frames = vec![];
+    for day in 0..=VIZ_DAYS {
+        if day > 0 {
+            fish.step();
+        }
+        let widest = fish.0.iter().copied().max().unwrap_or_default();
+        let mut frame = Frame::new();
+        frame.push(format!("Day {day}: {} fish", fish.count()), Colour::Plain);
+        for (age, &count) in fish.0.iter().enumerate() {
+            let colour = match age {
+                0 => Colour::Yellow,
+                7 | 8 => Colour::Green,
+                _ => Colour::Blue,
+            };
+            frame
+                .newline()
+                .push(format!("{age} |"), Colour::Plain)
+                .push(
+                    "#".repeat(viz::scale(count, widest, VIZ_BAR_WIDTH + 1)),
+                    colour,
+                )
+                .push(format!(" {count}"), Colour::Plain);
+        }
+        frames.push(frame);
+    }
+    frames
+}
+
+/// Lets the school grow a few days at a time for `aoc repl`.
+pub(crate) struct Explorer {
+    fish: Fish,
+    day: u16,
+}
+
+impl Explorer {
+    pub(crate) fn new(fish: Fish) -> Self {
+        Self { fish, day: 0 }
+    }
+
+    fn after(&self, num_days: u16) -> Result<(Fish, u16), String> {
+        let day = self
+            .day
+            .checked_add(num_days)
+            .ok_or("that's too far ahead")?;
+        let fish = self
+            .fish
+            .checked_after_days(num_days)
+            .ok_or("there are too many fish to count by then")?;
+        Ok((fish, day))
+    }
+}
+
+impl Explore for Explorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("fish", "how many fish there are of each age"),
+            (
+                "fish after N",
+                "the same, N days from now, without moving on",
+            ),
+            ("advance N", "moves on N days"),
+        ]
+    }
+
+    fn run(&mut self, words: &[&str]) -> Option<Result<String, String>> {
+        let days = |n| repl::number::<u16>(n, "a number of days");
+        Some(match *words {
+            ["fish"] => Ok(describe(&self.fish, self.day)),
+            ["fish", "after", n] => days(n).and_then(|n| {
+                let (fish, day) = self.after(n)?;
+                Ok(describe(&fish, day))
+            }),
+            ["advance", n] => days(n).and_then(|n| {
+                (self.fish, self.day) = self.after(n)?;
+                Ok(describe(&self.fish, self.day))
+            }),
+            _ => return None,
+        })
+    }
+}
+
+fn describe(fish: &Fish, day: u16) -> String {
+    let mut lines = vec![format!("day {day}: {} fish", fish.count())];
+    lines.extend(
+        fish.0
+            .iter()
+            .enumerate()
+            .map(|(age, count)| format!("  {age}: {count}")),
+    );
+    lines.join("\n")
 }
 
 impl Fish {
+    /// Parses a list of ages while compiling, for [`const_answer!`](crate::const_answer). Unlike
+    /// [`input_generator`] it also takes whitespace between the ages.
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn parse(input: &str) -> Self {
+        let bytes = input.as_bytes();
+        let mut fish = [0; 9];
+        let mut i = 0;
+        while let Some((age, next)) = comptime::next_number(bytes, i) {
+            fish[age as usize] += 1;
+            i = next;
+        }
+        Self(fish)
+    }
+
+    /// The school after `num_days` days. Unlike the solvers this can run at compile time, so it
+    /// isn't traced.
+    #[must_use]
+    pub const fn after_days(mut self, num_days: u16) -> Self {
+        let mut day = 0;
+        while day < num_days {
+            self.step();
+            day += 1;
+        }
+        self
+    }
+
+    // `rotate_left` by hand, since it can't be used in a `const fn`.
+    const fn step(&mut self) {
+        let spawning = self.0[0];
+        let mut age = 0;
+        while age < 8 {
+            self.0[age] = self.0[age + 1];
+            age += 1;
+        }
+        self.0[8] = spawning;
+        self.0[6] += spawning;
+    }
+
     fn advance_days(&mut self, num_days: u16) {
+        let cancel = cancel::current();
+        let mut progress = progress::start("days", u64::from(num_days));
+        for day in 1..=num_days {
+            cancel::check(cancel.as_ref());
+            self.step();
+            progress.advance(1);
+            if day % TRACE_BATCH == 0 || day == num_days {
+                event!("days_simulated", days = day, fish = self.count());
+            }
+        }
+    }
+
+    /// The school after `num_days` days, or `None` if there would be more fish than a `u64` can
+    /// count.
+    fn checked_after_days(&self, num_days: u16) -> Option<Self> {
+        let mut fish = self.clone();
         for _ in 0..num_days {
-            self.0.rotate_left(1);
-            self.0[6] += self.0[8];
+            let spawning = fish.0[0];
+            fish.0.rotate_left(1);
+            fish.0[6] = fish.0[6].checked_add(spawning)?;
+            fish.0
+                .iter()
+                .try_fold(0_u64, |count, &n| count.checked_add(n))?;
         }
+        Some(fish)
     }
 
-    fn count(&self) -> u64 {
-        self.0.iter().sum()
+    pub const fn count(&self) -> u64 {
+        let mut count = 0;
+        let mut age = 0;
+        while age < 9 {
+            count += self.0[age];
+            age += 1;
+        }
+        count
+    }
+}
+
+impl fmt::Display for Fish {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        for (age, &count) in self.0.iter().enumerate() {
+            for _ in 0..count {
+                if !first {
+                    write!(f, ",")?;
+                }
+                write!(f, "{age}")?;
+                first = false;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Encode for Fish {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        Encode::decode(input).map(Self)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::Fish;
+    use crate::random::Rng;
+
     const RAW_INPUT: &str = "3,4,3,1,2";
 
     #[test]
     fn input_generator() {
         let fish = super::input_generator(RAW_INPUT);
         assert_eq!(fish.0, [0, 1, 1, 2, 1, 0, 0, 0, 0,]);
+        assert_eq!(Fish::parse(RAW_INPUT), fish);
+        assert!(std::panic::catch_unwind(|| super::input_generator("3 4\n5")).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let fish = super::input_generator(RAW_INPUT);
+        let json = serde_json::to_string(&fish).unwrap();
+        assert_eq!(json, "[0,1,1,2,1,0,0,0,0]");
+        assert_eq!(serde_json::from_str::<super::Fish>(&json).unwrap(), fish);
+    }
+
+    #[test]
+    fn display() {
+        let fish = super::input_generator(RAW_INPUT);
+        assert_eq!(fish.to_string(), "1,2,3,3,4");
+
+        let mut rng = Rng::new(6);
+        for _ in 0..100 {
+            let mut fish = Fish::default();
+            for count in &mut fish.0 {
+                *count = rng.range(0..=5);
+            }
+            // An empty school can't be written as puzzle input.
+            fish.0[0] += 1;
+            assert_eq!(super::input_generator(&fish.to_string()), fish);
+        }
     }
 
     #[test]
     fn part1() {
         let fish = super::input_generator(RAW_INPUT);
-        assert_eq!(super::part1(&fish), 5934);
+        assert_eq!(super::part1(&fish), 5934.into());
     }
 
     #[test]
     fn part2() {
         let fish = super::input_generator(RAW_INPUT);
-        assert_eq!(super::part2(&fish), 26_984_457_539);
+        assert_eq!(super::part2(&fish), 26_984_457_539_u64.into());
+    }
+
+    #[test]
+    fn cancelled() {
+        use crate::cancel::{self, Cancelled, Token};
+
+        let fish = super::input_generator(RAW_INPUT);
+        let token = Token::new();
+        token.cancel();
+        cancel::set_current(Some(token));
+        let result = std::panic::catch_unwind(|| super::part2(&fish));
+        cancel::set_current(None);
+        assert!(result.unwrap_err().is::<Cancelled>());
+    }
+
+    #[test]
+    fn after_days() {
+        const FISH: Fish = Fish::parse(RAW_INPUT).after_days(18);
+        assert_eq!(FISH.count(), 26);
+        assert_eq!(FISH, super::input_generator(RAW_INPUT).after_days(18));
+        assert_eq!(Fish::parse(RAW_INPUT).checked_after_days(18), Some(FISH));
+        assert_eq!(Fish::parse(RAW_INPUT).checked_after_days(1000), None);
+    }
+
+    #[test]
+    fn growth() {
+        let fish = super::input_generator(RAW_INPUT);
+        let growth = super::growth(&fish, 80);
+        assert_eq!(growth[..3], [5, 6, 7]);
+        assert_eq!(growth[17], 26);
+        assert_eq!(growth[79], 5934);
+    }
+
+    #[test]
+    #[cfg(feature = "viz")]
+    fn frames() {
+        let fish = super::input_generator(RAW_INPUT);
+        let frames = super::frames(&fish);
+        assert_eq!(frames.len(), 81);
+        assert!(frames[0].text().starts_with("Day 0: 5 fish\n0 | 0\n1 |"));
+        assert!(frames[80].text().starts_with("Day 80: 5934 fish\n"));
     }
 }
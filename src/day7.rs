@@ -1,4 +1,11 @@
+use crate::answer::Answer;
+use crate::cache::Encode;
+use crate::cancel;
+use crate::progress;
+use crate::repl::{self, Explore};
+
 #[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CrabSubmarine {
     h_pos: u64,
 }
@@ -12,45 +19,111 @@ pub fn input_generator(input: &str) -> Vec<CrabSubmarine> {
 }
 
 #[aoc(day7, part1)]
-pub fn part1(input: &[CrabSubmarine]) -> u64 {
+pub fn part1(input: &[CrabSubmarine]) -> Answer {
     let positions: Vec<_> = input.iter().map(|s| s.h_pos).collect();
     let min = *positions.iter().min().unwrap();
     let max = *positions.iter().max().unwrap();
 
+    let cancel = cancel::current();
+    let mut progress = progress::start("positions", max - min + 1);
     (min..=max)
-        .map(|pos| {
-            input.iter().fold(0, |acc, c| {
-                let diff = if pos > c.h_pos {
-                    pos - c.h_pos
-                } else {
-                    c.h_pos - pos
-                };
-                acc + diff
-            })
+        .filter_map(|pos| {
+            cancel::check(cancel.as_ref());
+            progress.advance(1);
+            fuel(input, pos, linear)
         })
         .min()
-        .unwrap()
+        .expect("the fuel doesn't fit in a u64")
+        .into()
 }
 
 #[aoc(day7, part2)]
-pub fn part2(input: &[CrabSubmarine]) -> u64 {
+pub fn part2(input: &[CrabSubmarine]) -> Answer {
     let positions: Vec<_> = input.iter().map(|s| s.h_pos).collect();
     let min = *positions.iter().min().unwrap();
     let max = *positions.iter().max().unwrap();
 
+    let cancel = cancel::current();
+    let mut progress = progress::start("positions", max - min + 1);
     (min..=max)
-        .map(|pos| {
-            input.iter().fold(0, |acc, c| {
-                let diff = if pos > c.h_pos {
-                    pos - c.h_pos
-                } else {
-                    c.h_pos - pos
-                };
-                acc + (diff * (diff + 1) / 2)
-            })
+        .filter_map(|pos| {
+            cancel::check(cancel.as_ref());
+            progress.advance(1);
+            fuel(input, pos, triangular)
         })
         .min()
-        .unwrap()
+        .expect("the fuel doesn't fit in a u64")
+        .into()
+}
+
+/// The fuel to move every crab to `pos`, when moving `n` steps costs `cost(n)`, or `None` if it
+/// doesn't fit in a `u64`.
+fn fuel(crabs: &[CrabSubmarine], pos: u64, cost: fn(u64) -> Option<u64>) -> Option<u64> {
+    crabs.iter().try_fold(0_u64, |total, c| {
+        total.checked_add(cost(pos.abs_diff(c.h_pos))?)
+    })
+}
+
+#[allow(clippy::unnecessary_wraps)]
+fn linear(steps: u64) -> Option<u64> {
+    Some(steps)
+}
+
+fn triangular(steps: u64) -> Option<u64> {
+    // Halving the even factor first keeps the product in range whenever the result is.
+    if steps.is_multiple_of(2) {
+        (steps / 2).checked_mul(steps + 1)
+    } else {
+        steps.checked_mul(steps / 2 + 1)
+    }
+}
+
+/// Prices up positions for `aoc repl`.
+pub(crate) struct Explorer {
+    crabs: Vec<CrabSubmarine>,
+}
+
+impl Explorer {
+    pub(crate) fn new(crabs: Vec<CrabSubmarine>) -> Self {
+        Self { crabs }
+    }
+}
+
+impl Explore for Explorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("crabs", "how many crabs there are and where"),
+            (
+                "cost at N",
+                "the fuel to line every crab up at N, for each part",
+            ),
+        ]
+    }
+
+    fn run(&mut self, words: &[&str]) -> Option<Result<String, String>> {
+        Some(match *words {
+            ["crabs"] => {
+                let positions = self.crabs.iter().map(|c| c.h_pos);
+                Ok(format!(
+                    "{} crabs from {} to {}",
+                    self.crabs.len(),
+                    positions.clone().min().unwrap_or_default(),
+                    positions.max().unwrap_or_default()
+                ))
+            }
+            ["cost", "at", n] => repl::number(n, "a position").map(|pos| {
+                let cost = |cost| {
+                    fuel(&self.crabs, pos, cost).map_or("too much".to_string(), |f| f.to_string())
+                };
+                format!(
+                    "part 1: {} fuel\npart 2: {} fuel",
+                    cost(linear),
+                    cost(triangular)
+                )
+            }),
+            _ => return None,
+        })
+    }
 }
 
 impl CrabSubmarine {
@@ -59,6 +132,16 @@ impl CrabSubmarine {
     }
 }
 
+impl Encode for CrabSubmarine {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.h_pos.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> Option<Self> {
+        u64::decode(input).map(Self::new)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::CrabSubmarine;
@@ -84,15 +167,40 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        let subs = super::input_generator(RAW_INPUT);
+        let json = serde_json::to_string(&subs[..2]).unwrap();
+        assert_eq!(json, r#"[{"h_pos":16},{"h_pos":1}]"#);
+        let round_tripped: Vec<CrabSubmarine> = serde_json::from_str(&json).unwrap();
+        assert_eq!(round_tripped, subs[..2]);
+    }
+
     #[test]
     fn part1() {
         let subs = super::input_generator(RAW_INPUT);
-        assert_eq!(super::part1(&subs), 37);
+        assert_eq!(super::part1(&subs), 37.into());
+    }
+
+    #[test]
+    fn fuel() {
+        let subs = super::input_generator(RAW_INPUT);
+        assert_eq!(super::fuel(&subs, 2, super::linear), Some(37));
+        assert_eq!(super::fuel(&subs, 5, super::triangular), Some(168));
+        assert_eq!(super::fuel(&subs, 10, super::linear), Some(71));
+        assert_eq!(super::fuel(&subs, u64::MAX, super::linear), None);
+        assert_eq!(super::fuel(&subs, u64::MAX, super::triangular), None);
+        assert_eq!(super::triangular(u64::MAX), None);
+        assert_eq!(
+            super::triangular((1 << 32) - 1),
+            Some((1 << 31) * ((1 << 32) - 1))
+        );
     }
 
     #[test]
     fn part2() {
         let subs = super::input_generator(RAW_INPUT);
-        assert_eq!(super::part2(&subs), 168);
+        assert_eq!(super::part2(&subs), 168.into());
     }
 }
@@ -0,0 +1,114 @@
+use crate::lint;
+use std::fmt;
+
+/// Guesses below this confidence aren't worth reporting.
+pub const THRESHOLD: f64 = 0.5;
+
+/// A day an input could belong to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Guess {
+    pub day: u8,
+    /// From 0 to 1.
+    pub confidence: f64,
+}
+
+impl fmt::Display for Guess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {} ({:.0}%)", self.day, self.confidence * 100.0)
+    }
+}
+
+/// Every day `input` plausibly belongs to, most likely first.
+///
+/// A day's score is the share of the input its [linter](lint::lint) accepts. Some days' shapes are
+/// special cases of others', so a perfect match for the narrower day discounts the broader one:
+/// rows of bits also read as day 1 depths, and a list of 0-8 also reads as day 7 positions.
+#[must_use]
+pub fn detect(input: &str) -> Vec<Guess> {
+    if input.trim().is_empty() {
+        return vec![];
+    }
+    let mut scores: Vec<_> = (1..=7).map(|day| (day, score(day, input))).collect();
+    for (narrow, broad, discount) in [(3, 1, 0.3), (6, 7, 0.5)] {
+        if scores[narrow - 1].1 >= 1.0 {
+            scores[broad - 1].1 *= discount;
+        }
+    }
+
+    let mut guesses: Vec<_> = scores
+        .into_iter()
+        .filter(|&(_, confidence)| confidence >= THRESHOLD)
+        .map(|(day, confidence)| Guess { day, confidence })
+        .collect();
+    guesses.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    guesses
+}
+
+/// A warning if `input` doesn't look like it belongs to `day` but does look like another day.
+#[must_use]
+pub fn mismatch(day: u8, input: &str) -> Option<String> {
+    let guesses = detect(input);
+    if guesses.is_empty() || guesses.iter().any(|g| g.day == day) {
+        return None;
+    }
+    let looks_like: Vec<_> = guesses.iter().map(ToString::to_string).collect();
+    Some(format!(
+        "this doesn't look like a day {day} input, it looks like {}",
+        looks_like.join(" or ")
+    ))
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn score(day: u8, input: &str) -> f64 {
+    // What a diagnostic is counted against: a line, or an item for the single-line days.
+    let units = match day {
+        6 | 7 if input.trim().contains('\n') => return 0.0,
+        6 | 7 => input.trim().split(',').count(),
+        _ => input.lines().count(),
+    };
+    let problems = lint::lint(day, input).len();
+    1.0 - (problems as f64 / units.max(1) as f64).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run;
+
+    fn days(input: &str) -> Vec<u8> {
+        super::detect(input).iter().map(|g| g.day).collect()
+    }
+
+    #[test]
+    fn examples() {
+        for day in 1..=7 {
+            let path = run::fixture_dir(day).join("example.txt");
+            let input = std::fs::read_to_string(&path).unwrap();
+            let guesses = super::detect(&input);
+            assert_eq!(guesses[0].day, day, "{}: {guesses:?}", path.display());
+            assert!((guesses[0].confidence - 1.0).abs() < f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn detect() {
+        assert_eq!(days("3,4,3,1,2"), vec![6, 7]);
+        assert_eq!(days("16,1,2,0,4,2,7,1,2,14"), vec![7, 6]);
+        assert_eq!(days("forward 5\ndown 5\nup 3\n"), vec![2]);
+        assert_eq!(days("0,9 -> 5,9\n8,0 -> 0,8\n"), vec![5]);
+        assert_eq!(days("00100\n11110\n10110\n"), vec![3]);
+        assert_eq!(days("199\n200\n208\n"), vec![1]);
+        assert!(days("hello").is_empty());
+        assert!(days("").is_empty());
+    }
+
+    #[test]
+    fn mismatch() {
+        assert_eq!(super::mismatch(6, "3,4,3,1,2"), None);
+        assert_eq!(super::mismatch(7, "3,4,3,1,2"), None);
+        assert_eq!(
+            super::mismatch(5, "3,4,3,1,2").unwrap(),
+            "this doesn't look like a day 5 input, it looks like day 6 (100%) or day 7 (50%)"
+        );
+        assert_eq!(super::mismatch(1, "hello"), None);
+    }
+}
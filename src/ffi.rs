@@ -0,0 +1,431 @@
+//! A C API over the solver registry.
+//!
+//! The header, `ffi/aoc.h`, is generated from `src/ffi.rs` by the `header` test, so document the
+//! API there rather than in the header. Update it with
+//! `AOC_WRITE_HEADER=1 cargo test --features ffi --lib ffi`.
+//!
+//! Build it as a shared library with
+//! `cargo rustc --release --lib --features ffi --crate-type cdylib`.
+//!
+//! Ownership: strings returned through `answer` out-parameters are allocated here and must be
+//! released with [`aoc_string_free`]. Solver variant names are static and must not be freed.
+//! Input buffers are only borrowed for the duration of the call.
+
+use crate::registry::{self, SOLVERS};
+use crate::run::{self, Failure};
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::ptr;
+use std::slice;
+use std::sync::OnceLock;
+
+pub const AOC_OK: c_int = 0;
+/// A required pointer argument was `NULL`.
+pub const AOC_ERR_NULL: c_int = 1;
+/// The input or variant name wasn't valid UTF-8.
+pub const AOC_ERR_UTF8: c_int = 2;
+/// No solver is registered for that day, part and variant.
+pub const AOC_ERR_NOT_FOUND: c_int = 3;
+/// The input couldn't be parsed; `*answer` holds the error message.
+pub const AOC_ERR_GENERATING: c_int = 4;
+/// The solver failed on the parsed input; `*answer` holds the error message.
+pub const AOC_ERR_RUNNING: c_int = 5;
+
+/// A registered solver, as filled in by [`aoc_solver_get`].
+#[repr(C)]
+pub struct AocSolver {
+    pub day: u8,
+    pub part: u8,
+    /// `NULL` for a day and part's only solver.
+    pub variant: *const c_char,
+}
+
+// NUL-terminated copies of the registry's variant names, alive for the rest of the program.
+fn variants() -> &'static [Option<CString>] {
+    static VARIANTS: OnceLock<Vec<Option<CString>>> = OnceLock::new();
+    VARIANTS.get_or_init(|| {
+        SOLVERS
+            .iter()
+            .map(|s| s.variant.and_then(|v| CString::new(v).ok()))
+            .collect()
+    })
+}
+
+/// The number of registered solvers.
+#[no_mangle]
+pub extern "C" fn aoc_solver_count() -> usize {
+    SOLVERS.len()
+}
+
+/// Fills `out` with the solver at `index`, in registry order.
+///
+/// # Safety
+///
+/// `out` must be null or valid for writing an `aoc_solver`.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solver_get(index: usize, out: *mut AocSolver) -> c_int {
+    if out.is_null() {
+        return AOC_ERR_NULL;
+    }
+    let Some(solver) = SOLVERS.get(index) else {
+        return AOC_ERR_NOT_FOUND;
+    };
+    let variant = variants()[index]
+        .as_ref()
+        .map_or(ptr::null(), |v| v.as_ptr());
+    out.write(AocSolver {
+        day: solver.day,
+        part: solver.part,
+        variant,
+    });
+    AOC_OK
+}
+
+/// Solves `input` with the solver for `day`, `part` and `variant` (`NULL` for the default).
+///
+/// On success `*answer` is the answer, with one line per row for a grid. When the solver fails
+/// `*answer` is an error message instead, and for any other error it's `NULL`. Either way a
+/// non-null `*answer` must be released with [`aoc_string_free`].
+///
+/// # Safety
+///
+/// `variant` must be null or a NUL-terminated string, `input` must be valid for reading `len`
+/// bytes, and `answer` must be valid for writing a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(
+    day: u8,
+    part: u8,
+    variant: *const c_char,
+    input: *const u8,
+    len: usize,
+    answer: *mut *mut c_char,
+) -> c_int {
+    if answer.is_null() || (input.is_null() && len > 0) {
+        return AOC_ERR_NULL;
+    }
+    answer.write(ptr::null_mut());
+
+    let variant = if variant.is_null() {
+        None
+    } else {
+        match CStr::from_ptr(variant).to_str() {
+            Ok(variant) => Some(variant),
+            Err(_) => return AOC_ERR_UTF8,
+        }
+    };
+    let input = if len == 0 {
+        ""
+    } else {
+        match std::str::from_utf8(slice::from_raw_parts(input, len)) {
+            Ok(input) => input,
+            Err(_) => return AOC_ERR_UTF8,
+        }
+    };
+    let Some(solver) = registry::find(day, part, variant) else {
+        return AOC_ERR_NOT_FOUND;
+    };
+
+    let (code, text) = match run::run(solver, input).answer {
+        Ok(answer) => (AOC_OK, answer.to_string()),
+        Err(failure @ Failure::Generating(_)) => (AOC_ERR_GENERATING, failure.message()),
+        Err(failure) => (AOC_ERR_RUNNING, failure.message()),
+    };
+    // Neither answers nor panic messages should contain NUL, but don't hand back garbage if they do.
+    let text = CString::new(text.replace('\0', "")).unwrap_or_default();
+    answer.write(text.into_raw());
+    code
+}
+
+/// Releases a string returned by [`aoc_solve`]. Does nothing for `NULL`.
+///
+/// # Safety
+///
+/// `s` must be null or a string from [`aoc_solve`] that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn aoc_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // The helpers go unused when the days the tests solve are disabled.
+    #![cfg_attr(
+        not(all(feature = "day1", feature = "day2", feature = "day6", feature = "day7")),
+        allow(dead_code, unused_imports)
+    )]
+
+    use super::{AocSolver, AOC_ERR_GENERATING, AOC_ERR_NOT_FOUND, AOC_ERR_NULL, AOC_OK};
+    use std::ffi::{c_char, CStr};
+    use std::fmt::Write;
+    use std::fs;
+    use std::ptr;
+
+    const HEADER: &str = "ffi/aoc.h";
+    // Signatures are wrapped to fit in this many columns.
+    const WIDTH: usize = 80;
+
+    fn solve(day: u8, part: u8, variant: Option<&CStr>, input: &str) -> (i32, Option<String>) {
+        let mut answer: *mut c_char = ptr::null_mut();
+        unsafe {
+            let code = super::aoc_solve(
+                day,
+                part,
+                variant.map_or(ptr::null(), CStr::as_ptr),
+                input.as_ptr(),
+                input.len(),
+                &raw mut answer,
+            );
+            let text =
+                (!answer.is_null()).then(|| CStr::from_ptr(answer).to_string_lossy().into_owned());
+            super::aoc_string_free(answer);
+            (code, text)
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day2"))]
+    fn solvers() {
+        assert_eq!(super::aoc_solver_count(), crate::registry::SOLVERS.len());
+
+        let mut solver = AocSolver {
+            day: 0,
+            part: 0,
+            variant: ptr::null(),
+        };
+        unsafe {
+            assert_eq!(super::aoc_solver_get(1, &raw mut solver), AOC_OK);
+            assert_eq!((solver.day, solver.part), (1, 1));
+            assert_eq!(CStr::from_ptr(solver.variant), c"fold");
+
+            assert_eq!(super::aoc_solver_get(6, &raw mut solver), AOC_OK);
+            assert!(solver.variant.is_null());
+
+            assert_eq!(
+                super::aoc_solver_get(999, &raw mut solver),
+                AOC_ERR_NOT_FOUND
+            );
+            assert_eq!(super::aoc_solver_get(0, ptr::null_mut()), AOC_ERR_NULL);
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day6", feature = "day7"))]
+    fn aoc_solve() {
+        assert_eq!(
+            solve(6, 1, None, "3,4,3,1,2"),
+            (AOC_OK, Some("5934".to_string()))
+        );
+        assert_eq!(
+            solve(
+                1,
+                2,
+                Some(c"fold"),
+                "199\n200\n208\n210\n200\n207\n240\n269\n260\n263"
+            ),
+            (AOC_OK, Some("5".to_string()))
+        );
+
+        let (code, message) = solve(7, 1, None, "1,x");
+        assert_eq!(code, AOC_ERR_GENERATING);
+        assert!(message.is_some());
+
+        assert_eq!(solve(1, 1, Some(c"nope"), "1"), (AOC_ERR_NOT_FOUND, None));
+        assert_eq!(solve(9, 1, None, "1"), (AOC_ERR_NOT_FOUND, None));
+    }
+
+    #[test]
+    fn header() {
+        let generated = header_for(include_str!("ffi.rs"));
+        if std::env::var_os("AOC_WRITE_HEADER").is_some() {
+            fs::write(HEADER, &generated).unwrap();
+        }
+        assert!(
+            fs::read_to_string(HEADER).unwrap() == generated,
+            "{HEADER} is out of date, run this test with AOC_WRITE_HEADER=1 to update it"
+        );
+    }
+
+    // The C header declaring the public items of `source`, which is this file: constants become
+    // `#define`s, `#[repr(C)]` structs become typedefs, and `extern "C"` functions become
+    // prototypes, each with its doc comment.
+    fn header_for(source: &str) -> String {
+        let (source, _) = source.split_once("#[cfg(test)]").unwrap();
+        let mut lines = source.lines();
+
+        let preamble: Vec<_> = lines
+            .by_ref()
+            .map_while(|l| l.strip_prefix("//!"))
+            .map(doc_line)
+            .collect();
+        let mut out = comment("", &preamble);
+        out.push_str(
+            "\n#ifndef AOC_H\n#define AOC_H\n\n#include <stddef.h>\n#include <stdint.h>\n",
+        );
+        out.push_str("\n#ifdef __cplusplus\nextern \"C\" {\n#endif\n");
+
+        let mut docs = vec![];
+        let mut after_define = false;
+        while let Some(line) = lines.next() {
+            if let Some(doc) = line.strip_prefix("///") {
+                docs.push(doc_line(doc));
+                continue;
+            }
+            if line.starts_with("#[") {
+                continue;
+            }
+            if let Some(define) = line.strip_prefix("pub const ") {
+                let (name, value) = define.split_once(": c_int = ").unwrap();
+                if !after_define {
+                    out.push('\n');
+                }
+                out.push_str(&comment("", &docs));
+                writeln!(out, "#define {name} {}", value.trim_end_matches(';')).unwrap();
+                after_define = true;
+            } else if let Some(name) = line.strip_prefix("pub struct ") {
+                let name = c_name(name.trim_end_matches(" {"));
+                out.push('\n');
+                out.push_str(&comment("", &docs));
+                writeln!(out, "typedef struct {name} {{").unwrap();
+                docs.clear();
+                for field in lines.by_ref().map(str::trim).take_while(|l| *l != "}") {
+                    if let Some(doc) = field.strip_prefix("///") {
+                        docs.push(doc_line(doc));
+                    } else {
+                        let (name, ty) = field.trim_start_matches("pub ").split_once(": ").unwrap();
+                        out.push_str(&comment("    ", &docs));
+                        writeln!(out, "    {};", decl(ty.trim_end_matches(','), name)).unwrap();
+                        docs.clear();
+                    }
+                }
+                writeln!(out, "}} {name};").unwrap();
+                after_define = false;
+            } else if let Some((_, signature)) = line.split_once("extern \"C\" fn ") {
+                let mut signature = signature.to_string();
+                while !signature.ends_with('{') {
+                    signature.push_str(lines.next().unwrap().trim());
+                }
+                out.push('\n');
+                out.push_str(&comment("", &docs));
+                out.push_str(&prototype(signature.trim_end_matches(" {")));
+                after_define = false;
+            }
+            docs.clear();
+        }
+
+        out.push_str("\n#ifdef __cplusplus\n}\n#endif\n\n#endif /* AOC_H */\n");
+        out
+    }
+
+    // A doc comment line as C comment text: intra-doc links become plain function names and
+    // section headings become labels.
+    fn doc_line(doc: &str) -> String {
+        let mut doc = doc.strip_prefix(' ').unwrap_or(doc).to_string();
+        while let Some((before, rest)) = doc.split_once("[`") {
+            let (name, after) = rest.split_once("`]").unwrap();
+            doc = format!("{before}{name}(){after}");
+        }
+        match doc.strip_prefix("# ") {
+            Some(heading) => format!("{heading}:"),
+            None => doc,
+        }
+    }
+
+    fn comment(indent: &str, docs: &[impl AsRef<str>]) -> String {
+        match docs {
+            [] => String::new(),
+            [line] => format!("{indent}/* {} */\n", line.as_ref()),
+            lines => {
+                let mut out = format!("{indent}/*\n");
+                for line in lines.iter().map(AsRef::as_ref) {
+                    let gap = if line.is_empty() { "" } else { " " };
+                    writeln!(out, "{indent} *{gap}{line}").unwrap();
+                }
+                out + &format!("{indent} */\n")
+            }
+        }
+    }
+
+    fn prototype(signature: &str) -> String {
+        let (name, rest) = signature.split_once('(').unwrap();
+        let (args, returns) = rest.rsplit_once(')').unwrap();
+        let returns = returns
+            .trim()
+            .strip_prefix("-> ")
+            .map_or("void".to_string(), c_type);
+        let mut args: Vec<_> = args
+            .split(',')
+            .filter_map(|arg| arg.trim().split_once(": "))
+            .map(|(name, ty)| decl(ty, name))
+            .collect();
+        if args.is_empty() {
+            args.push("void".to_string());
+        }
+
+        // Wrap the arguments, lining them up after the opening parenthesis.
+        let start = format!("{returns} {name}(");
+        let (mut out, mut line) = (String::new(), start.clone());
+        for (i, arg) in args.iter().enumerate() {
+            let arg = format!("{arg}{}", if i + 1 == args.len() { ");" } else { "," });
+            if i == 0 {
+                line.push_str(&arg);
+            } else if line.len() + 1 + arg.len() > WIDTH {
+                out.push_str(&line);
+                out.push('\n');
+                line = format!("{}{arg}", " ".repeat(start.len()));
+            } else {
+                line.push(' ');
+                line.push_str(&arg);
+            }
+        }
+        out + &line + "\n"
+    }
+
+    fn decl(ty: &str, name: &str) -> String {
+        let ty = c_type(ty);
+        if ty.ends_with('*') {
+            format!("{ty}{name}")
+        } else {
+            format!("{ty} {name}")
+        }
+    }
+
+    fn c_type(ty: &str) -> String {
+        let pointer = |pointee: String| {
+            if pointee.ends_with('*') {
+                pointee + "*"
+            } else {
+                pointee + " *"
+            }
+        };
+        if let Some(pointee) = ty.strip_prefix("*const ") {
+            assert!(
+                !pointee.starts_with('*'),
+                "only the innermost pointer can be const"
+            );
+            pointer(format!("const {}", c_type(pointee)))
+        } else if let Some(pointee) = ty.strip_prefix("*mut ") {
+            pointer(c_type(pointee))
+        } else {
+            match ty {
+                "u8" => "uint8_t".to_string(),
+                "usize" => "size_t".to_string(),
+                "c_int" => "int".to_string(),
+                "c_char" => "char".to_string(),
+                _ => c_name(ty),
+            }
+        }
+    }
+
+    // `AocSolver` is `aoc_solver` in C.
+    fn c_name(name: &str) -> String {
+        let mut c = String::new();
+        for ch in name.chars() {
+            if ch.is_ascii_uppercase() && !c.is_empty() {
+                c.push('_');
+            }
+            c.push(ch.to_ascii_lowercase());
+        }
+        c
+    }
+}
@@ -0,0 +1,134 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// A rectangle of cells stored row by row, indexed by `(x, y)`.
+///
+/// A `Grid<bool>` is a picture, with `true` for a lit pixel, written with `#` for lit pixels and
+/// `.` for the rest.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T = bool> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    #[must_use]
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T: Clone + Default> Grid<T> {
+    /// A grid as wide as the longest row, with shorter rows filled out with the default.
+    pub fn from_rows<R: AsRef<[T]>>(rows: &[R]) -> Self {
+        let width = rows.iter().map(|r| r.as_ref().len()).max().unwrap_or(0);
+        let mut grid = Self::new(width, rows.len(), T::default());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, cell) in row.as_ref().iter().enumerate() {
+                grid[(x, y)] = cell.clone();
+            }
+        }
+        grid
+    }
+}
+
+impl<T> Grid<T> {
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The cell at `(x, y)`, or `None` if that's outside the grid.
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &[T]> + DoubleEndedIterator + '_ {
+        (0..self.height).map(|y| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+}
+
+impl Grid {
+    /// Reads a picture drawn with `#` for lit pixels, one line per row. Any other character is
+    /// unlit.
+    #[must_use]
+    pub fn from_text(picture: &str) -> Self {
+        let rows: Vec<Vec<bool>> = picture
+            .lines()
+            .map(|line| line.chars().map(|c| c == '#').collect())
+            .collect();
+        Self::from_rows(&rows)
+    }
+
+    /// The picture written with `sep` between rows.
+    #[must_use]
+    pub fn text(&self, sep: &str) -> String {
+        self.rows()
+            .map(|row| row.iter().map(|&p| if p { '#' } else { '.' }).collect())
+            .collect::<Vec<String>>()
+            .join(sep)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is outside the grid"
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is outside the grid"
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+
+    #[test]
+    fn from_rows() {
+        let grid = Grid::from_rows(&[vec![1, 2, 3], vec![4]]);
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 0)], 3);
+        assert_eq!(grid[(1, 1)], 0);
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 2, 3], [4, 0, 0]]);
+    }
+
+    #[test]
+    fn text() {
+        let grid = Grid::from_text("#..#\n##");
+        assert_eq!(grid.to_string(), "#..#\n##..");
+        assert_eq!(grid.text("/"), "#..#/##..");
+        assert_eq!(Grid::from_text(""), Grid::new(0, 0, false));
+    }
+}
@@ -0,0 +1,159 @@
+use crate::answer::Answer;
+use crate::answers::Verdict;
+use crate::registry::Solver;
+use crate::run::Run;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Bumped whenever a field of [`result`] is renamed, removed or changes meaning.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Which solver this is.
+#[must_use]
+pub fn describe(solver: &Solver) -> Value {
+    json!({
+        "day": solver.day,
+        "part": solver.part,
+        "variant": solver.variant,
+    })
+}
+
+/// Everything about one run of `solver`, in the shape `--format json` prints and CI compares.
+///
+/// Every key is always present, with `null` for whatever doesn't apply, so consumers don't have to
+/// tell a missing field from a failed run.
+///
+/// Answers are always strings so big numbers survive every JSON parser, with `answer_type` saying
+/// how to read them: `integer`, `big_integer` (beyond 64 bits), `text` or `grid` (rows separated by
+/// newlines).
+#[must_use]
+pub fn result(solver: &Solver, run: &Run, verdict: &Verdict) -> Value {
+    let mut body = describe(solver);
+    body["schema"] = SCHEMA_VERSION.into();
+    body["timings"] = timings(run);
+    body["cached"] = run.cache_hit.into();
+    match &run.answer {
+        Ok(answer) => {
+            body["answer"] = answer.to_string().into();
+            body["answer_type"] = answer.kind().into();
+            body["error"] = Value::Null;
+        }
+        Err(failure) => {
+            body["answer"] = Value::Null;
+            body["answer_type"] = Value::Null;
+            body["error"] = error(failure.stage(), &failure.message());
+        }
+    }
+    body["verification"] = match verdict {
+        Verdict::Correct => {
+            let expected = run.answer.as_ref().ok().map(Answer::inline);
+            json!({ "status": "correct", "expected": expected })
+        }
+        Verdict::Wrong { expected } => json!({ "status": "wrong", "expected": expected }),
+        Verdict::Unknown => json!({ "status": "unknown", "expected": null }),
+    };
+    body
+}
+
+/// A [`result`] for a solver that couldn't be run at all because its input couldn't be read.
+#[must_use]
+pub fn missing_input(solver: &Solver, message: &str) -> Value {
+    let mut body = describe(solver);
+    body["schema"] = SCHEMA_VERSION.into();
+    body["timings"] = json!({ "generator_ns": null, "solver_ns": null });
+    body["cached"] = false.into();
+    body["answer"] = Value::Null;
+    body["answer_type"] = Value::Null;
+    body["error"] = error("input", message);
+    body["verification"] = json!({ "status": "unknown", "expected": null });
+    body
+}
+
+#[must_use]
+pub fn timings(run: &Run) -> Value {
+    json!({
+        "generator_ns": nanos(run.generator),
+        "solver_ns": nanos(run.solver),
+    })
+}
+
+#[must_use]
+pub fn error(stage: &str, message: &str) -> Value {
+    json!({ "stage": stage, "message": message })
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(feature = "day1")]
+    fn result() {
+        use crate::answers::Verdict;
+        use crate::{registry, run};
+
+        let solver = registry::find(1, 2, Some("fold")).unwrap();
+        let run = run::run(solver, "199\n200\n208\n210\n200\n207\n240\n269\n260\n263");
+        let json = super::result(solver, &run, &Verdict::Correct);
+
+        assert_eq!(json["schema"], 2);
+        assert_eq!(json["day"], 1);
+        assert_eq!(json["variant"], "fold");
+        assert_eq!(json["answer"], "5");
+        assert_eq!(json["answer_type"], "integer");
+        assert!(json["timings"]["solver_ns"].is_u64());
+        assert_eq!(json["error"], serde_json::Value::Null);
+        assert_eq!(
+            json["verification"],
+            serde_json::json!({ "status": "correct", "expected": "5" })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "day7")]
+    fn failure() {
+        use crate::answers::Verdict;
+        use crate::{registry, run};
+
+        let solver = registry::find(7, 1, None).unwrap();
+        let run = run::run(solver, "1,x");
+        let json = super::result(solver, &run, &Verdict::Unknown);
+
+        assert_eq!(json["variant"], serde_json::Value::Null);
+        assert_eq!(json["answer"], serde_json::Value::Null);
+        assert_eq!(json["error"]["stage"], "generating");
+        assert_eq!(json["verification"]["status"], "unknown");
+
+        let missing = super::missing_input(solver, "no such file");
+        let keys =
+            |v: &serde_json::Value| v.as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+        assert_eq!(keys(&missing), keys(&json));
+    }
+
+    #[test]
+    #[cfg(feature = "day6")]
+    fn answer_type() {
+        use crate::answer::Answer;
+        use crate::answers::Verdict;
+        use crate::grid::Grid;
+        use crate::{registry, run};
+
+        let solver = registry::find(6, 2, None).unwrap();
+        let mut run = run::run(solver, "3,4,3,1,2");
+        let answer_type = |run: &run::Run| {
+            let json = super::result(solver, run, &Verdict::Unknown);
+            (json["answer"].clone(), json["answer_type"].clone())
+        };
+        assert_eq!(answer_type(&run), ("26984457539".into(), "integer".into()));
+
+        run.answer = Ok(Answer::from(u64::MAX));
+        assert_eq!(
+            answer_type(&run),
+            ("18446744073709551615".into(), "big_integer".into())
+        );
+        run.answer = Ok(Answer::Grid(Grid::from_text("#.\n.#")));
+        assert_eq!(answer_type(&run), ("#.\n.#".into(), "grid".into()));
+    }
+}
@@ -1,16 +1,73 @@
 #![deny(clippy::all, clippy::pedantic, rust_2018_idioms)]
 
+// Only the day modules use the derive's macros.
+#[cfg_attr(
+    not(any(
+        feature = "day1",
+        feature = "day2",
+        feature = "day3",
+        feature = "day4",
+        feature = "day5",
+        feature = "day6",
+        feature = "day7"
+    )),
+    allow(unused_imports)
+)]
 #[macro_use]
 extern crate aoc_runner_derive;
 
 use aoc_runner_derive::aoc_lib;
 
+#[cfg(feature = "day1")]
 mod day1;
+#[cfg(feature = "day2")]
 mod day2;
+#[cfg(feature = "day3")]
 mod day3;
+#[cfg(feature = "day4")]
 mod day4;
+#[cfg(feature = "day5")]
 mod day5;
+#[cfg(feature = "day6")]
 mod day6;
+#[cfg(feature = "day7")]
 mod day7;
 
+/// Day 1's depth analysis, which works on more than the puzzle's input.
+#[cfg(feature = "day1")]
+pub use day1::{count_windows, Comparison, SonarAnalyzer, Trend};
+
+pub mod alloc;
+pub mod answer;
+pub mod answers;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod cache;
+pub mod cancel;
+pub mod comptime;
+pub mod dashboard;
+pub mod detect;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod grid;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod lint;
+pub mod minimize;
+pub mod ocr;
+#[cfg(feature = "bench")]
+pub mod profile;
+pub mod progress;
+pub mod random;
+pub mod registry;
+pub mod repl;
+pub mod report;
+pub mod run;
+#[cfg(feature = "serve")]
+pub mod serve;
+pub mod trace;
+#[cfg(feature = "viz")]
+pub mod viz;
+pub mod watch;
+
 aoc_lib! { year = 2021 }
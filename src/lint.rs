@@ -0,0 +1,292 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A problem with one line of an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 1-based, like an editor's. `0` means the input as a whole.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn new(line: usize, message: impl Into<String>) -> Self {
+        Self {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+/// Checks `input` against the structure `day`'s generator and solvers assume.
+///
+/// An empty result means the input is fine as far as the linter can tell, not that the solvers
+/// will succeed on it.
+#[must_use]
+pub fn lint(day: u8, input: &str) -> Vec<Diagnostic> {
+    let lines = input.lines().enumerate().map(|(i, line)| (i + 1, line));
+    let mut diagnostics = match day {
+        1 => day1(lines),
+        2 => day2(lines),
+        3 => day3(lines),
+        4 => day4(lines),
+        5 => day5(lines),
+        6 => day6(input),
+        7 => day7(input),
+        _ => vec![Diagnostic::new(0, format!("no linter for day {day}"))],
+    };
+    if input.trim().is_empty() && diagnostics.is_empty() {
+        diagnostics.push(Diagnostic::new(0, "the input is empty"));
+    }
+    diagnostics
+}
+
+fn day1<'a>(lines: impl Iterator<Item = (usize, &'a str)>) -> Vec<Diagnostic> {
+    lines
+        .filter(|(_, line)| line.parse::<u64>().is_err())
+        .map(|(n, line)| Diagnostic::new(n, format!("`{line}` isn't a depth")))
+        .collect()
+}
+
+fn day2<'a>(lines: impl Iterator<Item = (usize, &'a str)>) -> Vec<Diagnostic> {
+    lines
+        .filter_map(|(n, line)| match line.split_once(' ') {
+            Some(("forward" | "down" | "up", amount)) if amount.parse::<u64>().is_ok() => None,
+            Some(("forward" | "down" | "up", amount)) => {
+                Some(Diagnostic::new(n, format!("`{amount}` isn't a distance")))
+            }
+            _ => Some(Diagnostic::new(
+                n,
+                format!("`{line}` isn't forward, down or up followed by a distance"),
+            )),
+        })
+        .collect()
+}
+
+fn day3<'a>(lines: impl Iterator<Item = (usize, &'a str)>) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let mut width = None;
+    let mut seen = HashMap::new();
+    for (n, line) in lines {
+        if !line.chars().all(|c| c == '0' || c == '1') {
+            diagnostics.push(Diagnostic::new(n, "only 0 and 1 are allowed"));
+            continue;
+        }
+        match width {
+            None => width = Some((n, line.len())),
+            Some((first, width)) if line.len() != width => diagnostics.push(Diagnostic::new(
+                n,
+                format!("{} bits wide, but line {first} is {width}", line.len()),
+            )),
+            Some(_) => {}
+        }
+        // With two equal rows the part 2 winnow can never narrow down to a single rating.
+        if let Some(first) = seen.insert(line, n) {
+            diagnostics.push(Diagnostic::new(n, format!("duplicate of line {first}")));
+        }
+    }
+    diagnostics
+}
+
+fn day4<'a>(mut lines: impl Iterator<Item = (usize, &'a str)>) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let Some((_, draw_line)) = lines.next() else {
+        return diagnostics;
+    };
+    let mut drawn = HashSet::new();
+    for draw in draw_line.split(',') {
+        match draw.parse::<u8>() {
+            Ok(number) => {
+                drawn.insert(number);
+            }
+            Err(_) => diagnostics.push(Diagnostic::new(1, format!("`{draw}` isn't a number"))),
+        }
+    }
+
+    let mut board: Vec<(usize, &str)> = vec![];
+    let mut boards = 0;
+    for (n, line) in lines.chain([(0, "")]) {
+        if !line.trim().is_empty() {
+            board.push((n, line));
+            continue;
+        }
+        if board.is_empty() {
+            continue;
+        }
+        boards += 1;
+        let start = board[0].0;
+        if board.len() != 5 {
+            diagnostics.push(Diagnostic::new(
+                start,
+                format!("board has {} rows instead of 5", board.len()),
+            ));
+        }
+        let mut numbers = HashMap::new();
+        for &(n, row) in &board {
+            let row: Vec<_> = row.split_whitespace().collect();
+            if row.len() != 5 {
+                diagnostics.push(Diagnostic::new(
+                    n,
+                    format!("row has {} numbers instead of 5", row.len()),
+                ));
+            }
+            for number in row {
+                let Ok(number) = number.parse::<u8>() else {
+                    diagnostics.push(Diagnostic::new(n, format!("`{number}` isn't a number")));
+                    continue;
+                };
+                if let Some(first) = numbers.insert(number, n) {
+                    diagnostics.push(Diagnostic::new(
+                        n,
+                        format!("{number} is already on this board at line {first}"),
+                    ));
+                }
+                if !drawn.contains(&number) {
+                    diagnostics.push(Diagnostic::new(n, format!("{number} is never drawn")));
+                }
+            }
+        }
+        board.clear();
+    }
+    if boards == 0 {
+        diagnostics.push(Diagnostic::new(
+            1,
+            "the draw line isn't followed by any boards",
+        ));
+    }
+    diagnostics
+}
+
+fn day5<'a>(lines: impl Iterator<Item = (usize, &'a str)>) -> Vec<Diagnostic> {
+    let point = |s: &str| {
+        let (x, y) = s.split_once(',')?;
+        Some((x.trim().parse::<u16>().ok()?, y.trim().parse::<u16>().ok()?))
+    };
+    lines
+        .filter_map(|(n, line)| {
+            let Some(((x0, y0), (x1, y1))) = line
+                .split_once(" -> ")
+                .and_then(|(start, end)| Some((point(start)?, point(end)?)))
+            else {
+                return Some(Diagnostic::new(n, "expected `x1,y1 -> x2,y2`"));
+            };
+            let (dx, dy) = (x0.abs_diff(x1), y0.abs_diff(y1));
+            (dx != 0 && dy != 0 && dx != dy).then(|| {
+                Diagnostic::new(
+                    n,
+                    "segment is neither horizontal, vertical nor at exactly 45 degrees",
+                )
+            })
+        })
+        .collect()
+}
+
+// Days 6 and 7 are a single comma-separated line, so diagnostics point at the position in the list.
+fn numbers(input: &str) -> impl Iterator<Item = (usize, Result<u64, &str>)> {
+    input
+        .trim()
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .enumerate()
+        .map(|(i, s)| (i + 1, s.trim().parse().map_err(|_| s)))
+}
+
+fn day6(input: &str) -> Vec<Diagnostic> {
+    numbers(input)
+        .filter_map(|(i, age)| match age {
+            Ok(0..=8) => None,
+            Ok(age) => Some(format!("age {age} at position {i} is outside 0-8")),
+            Err(age) => Some(format!("`{age}` at position {i} isn't an age")),
+        })
+        .map(|message| Diagnostic::new(1, message))
+        .collect()
+}
+
+fn day7(input: &str) -> Vec<Diagnostic> {
+    numbers(input)
+        .filter_map(|(i, position)| {
+            position
+                .err()
+                .map(|p| Diagnostic::new(1, format!("`{p}` at position {i} isn't a position")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{lint, Diagnostic};
+
+    fn lines(day: u8, input: &str) -> Vec<usize> {
+        lint(day, input).iter().map(|d| d.line).collect()
+    }
+
+    #[test]
+    fn examples() {
+        for day in 1..=7 {
+            let path = crate::run::fixture_dir(day).join("example.txt");
+            let input = std::fs::read_to_string(&path).unwrap();
+            assert_eq!(lint(day, &input), vec![], "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn day3() {
+        assert_eq!(lines(3, "00100\n11110\n0010\n00100\n0a100"), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn day4() {
+        let board = "1 2 3 4 5\n6 7 8 9 10\n11 12 13 14 15\n16 17 18 19 20\n21 22 23 24 25";
+        let draws: Vec<_> = (1..=25).map(|n| n.to_string()).collect();
+        let input = format!("{}\n\n{board}\n\n{board}\n", draws.join(","));
+        assert_eq!(lint(4, &input), vec![]);
+
+        let input = format!("1,2,x\n\n{board}\n\n1 2 3 4 5\n6 6 7 8 9\n");
+        let diagnostics = lint(4, &input);
+        assert_eq!(diagnostics[0], Diagnostic::new(1, "`x` isn't a number"));
+        assert!(diagnostics.contains(&Diagnostic::new(9, "board has 2 rows instead of 5")));
+        assert!(diagnostics.contains(&Diagnostic::new(
+            10,
+            "6 is already on this board at line 10"
+        )));
+        assert!(diagnostics.contains(&Diagnostic::new(4, "6 is never drawn")));
+    }
+
+    #[test]
+    fn day5() {
+        assert_eq!(
+            lines(5, "0,9 -> 5,9\n8,0 -> 0,8\n1,1 -> 3,4\n1,1 -> 3\n"),
+            vec![3, 4]
+        );
+    }
+
+    #[test]
+    fn day6() {
+        assert_eq!(
+            lint(6, "3,4,9,1,x\n"),
+            vec![
+                Diagnostic::new(1, "age 9 at position 3 is outside 0-8"),
+                Diagnostic::new(1, "`x` at position 5 isn't an age"),
+            ]
+        );
+    }
+
+    #[test]
+    fn day7() {
+        assert_eq!(lint(7, "16,1,2\n"), vec![]);
+        assert_eq!(
+            lint(7, "\n"),
+            vec![Diagnostic::new(0, "the input is empty")]
+        );
+    }
+}
@@ -1,5 +1,794 @@
 #![deny(clippy::all, clippy::pedantic, rust_2018_idioms)]
 
-use aoc_runner_derive::aoc_main;
+use aoc::answer::Answer;
+#[cfg(feature = "bench")]
+use aoc::bench;
+use aoc::cache::Cache;
+use aoc::dashboard::InputSet;
+use aoc::minimize::{self, Check};
+#[cfg(feature = "bench")]
+use aoc::profile;
+use aoc::registry::SOLVERS;
+use aoc::repl::{self, Repl};
+use aoc::run::{self, Options, YEAR};
+use aoc::trace;
+use aoc::watch::Watch;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
 
-aoc_main! { lib = aoc }
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOCATOR: aoc::alloc::Counting = aoc::alloc::Counting;
+
+fn main() -> ExitCode {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    #[cfg(feature = "viz")]
+    if args.iter().any(|arg| arg == "--visualize") {
+        return visualize(args.into_iter().filter(|arg| arg != "--visualize"));
+    }
+    let mut args = args.into_iter().peekable();
+    match args.peek().cloned() {
+        None => run_all(&Options::default(), Format::Text, None),
+        Some(flag) if flag.starts_with("--") => {
+            let mut options = Options::default();
+            let mut format = Format::Text;
+            let mut trace = None;
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--format" => {
+                        format = match args.next().as_deref() {
+                            Some("text") => Format::Text,
+                            #[cfg(feature = "json")]
+                            Some("json") => Format::Json,
+                            #[cfg(not(feature = "json"))]
+                            Some("json") => {
+                                eprintln!("built without the `json` feature");
+                                return ExitCode::FAILURE;
+                            }
+                            _ => {
+                                eprintln!("usage: --format text|json");
+                                return ExitCode::FAILURE;
+                            }
+                        };
+                    }
+                    "--trace" => {
+                        trace = match args.next().as_deref() {
+                            Some("tree") => Some(TraceFormat::Tree),
+                            Some("json") => Some(TraceFormat::JsonLines),
+                            _ => {
+                                eprintln!("usage: --trace tree|json");
+                                return ExitCode::FAILURE;
+                            }
+                        };
+                        if !cfg!(feature = "trace") {
+                            eprintln!("warning: built without the `trace` feature, nothing will be traced");
+                        }
+                        options.trace = true;
+                    }
+                    _ if run_option(&mut options, &arg, &mut args) => {}
+                    _ => {
+                        eprintln!("unexpected argument: {arg}");
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+            run_all(&options, format, trace)
+        }
+        Some(command) => {
+            args.next();
+            subcommand(&command, args)
+        }
+    }
+}
+
+/// Handles the flags that control how solvers are run, returning whether `arg` was one of them.
+fn run_option(options: &mut Options, arg: &str, args: &mut impl Iterator<Item = String>) -> bool {
+    match arg {
+        "--cache" => options.cache = Some(Cache::default()),
+        "--progress" => options.progress = Some(aoc::progress::stderr()),
+        "--timeout" => {
+            options.timeout = args
+                .next()
+                .and_then(|secs| secs.parse().ok())
+                .map(Duration::from_secs_f64);
+        }
+        _ => return false,
+    }
+    true
+}
+
+fn subcommand(command: &str, args: impl Iterator<Item = String>) -> ExitCode {
+    match command {
+        #[cfg(feature = "serve")]
+        "serve" => serve(args),
+        #[cfg(feature = "bench")]
+        "bench" => bench(args),
+        #[cfg(feature = "bench")]
+        "profile" => profile(args),
+        "dashboard" => dashboard(args),
+        "watch" => watch(args),
+        "minimize" => minimize(args),
+        "lint" => lint(args),
+        "detect" => detect(args),
+        "repl" => repl(args),
+        "report" => report(args),
+        other => {
+            eprintln!("unknown command: {other}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    /// One JSON object per line for each solver, see `aoc::json::result`.
+    #[cfg(feature = "json")]
+    Json,
+}
+
+#[derive(Clone, Copy)]
+enum TraceFormat {
+    Tree,
+    JsonLines,
+}
+
+fn run_all(options: &Options, format: Format, trace: Option<TraceFormat>) -> ExitCode {
+    if format == Format::Text {
+        println!("Advent of code {YEAR}");
+    }
+
+    let mut input = None;
+    for solver in SOLVERS {
+        let path = run::input_path(solver.day);
+        if input.as_ref().is_none_or(|(day, _)| *day != solver.day) {
+            let contents = fs::read_to_string(&path)
+                .map_err(|e| format!("can't read {}: {e}", path.display()));
+            match &contents {
+                Err(e) if format == Format::Text => eprintln!("Day {}: {e}\n", solver.day),
+                Err(_) => {}
+                Ok(contents) => {
+                    if let Some(warning) = aoc::detect::mismatch(solver.day, contents) {
+                        eprintln!("Day {}: warning: {warning}\n", solver.day);
+                    }
+                }
+            }
+            input = Some((solver.day, contents));
+        }
+        let Some((_, Ok(contents))) = &input else {
+            #[cfg(feature = "json")]
+            if let (Some((_, Err(e))), Format::Json) = (&input, format) {
+                println!("{}", aoc::json::missing_input(solver, e));
+            }
+            continue;
+        };
+
+        let run = run::run_with(solver, contents, options);
+        match format {
+            Format::Text => {
+                let cached = if run.cache_hit { " (cached)" } else { "" };
+                match &run.answer {
+                    Ok(answer) => {
+                        // Start pictures on their own line so their rows line up.
+                        let sep = if matches!(answer, Answer::Grid(_)) {
+                            "\n"
+                        } else {
+                            " "
+                        };
+                        println!(
+                            "{solver}:{sep}{answer}\n\tgenerator: {:?}{cached},\n\trunner: {:?}\n",
+                            run.generator, run.solver
+                        );
+                    }
+                    Err(failure) => eprintln!("{solver}: {failure}\n"),
+                }
+            }
+            #[cfg(feature = "json")]
+            Format::Json => {
+                let verdict = match &run.answer {
+                    Ok(answer) => aoc::answers::for_input(&path)
+                        .unwrap_or_default()
+                        .verify(solver.part, answer),
+                    Err(_) => aoc::answers::Verdict::Unknown,
+                };
+                println!("{}", aoc::json::result(solver, &run, &verdict));
+            }
+        }
+
+        let written = match trace {
+            Some(TraceFormat::Tree) => trace::write_tree(&mut io::stderr(), &run.trace),
+            #[cfg(feature = "trace")]
+            Some(TraceFormat::JsonLines) => trace::write_json_lines(&mut io::stderr(), &run.trace),
+            _ => Ok(()),
+        };
+        if let Err(e) = written {
+            eprintln!("can't write trace: {e}");
+        }
+    }
+
+    ExitCode::SUCCESS
+}
+
+#[cfg(feature = "bench")]
+fn bench(mut args: impl Iterator<Item = String>) -> ExitCode {
+    let mut samples = bench::DEFAULT_SAMPLES;
+    let mut compare = false;
+    let mut threshold = bench::DEFAULT_THRESHOLD;
+    let mut input = InputSet::Puzzle;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--compare" => compare = true,
+            "--samples" => samples = args.next().and_then(|n| n.parse().ok()).unwrap_or(0),
+            "--threshold" => {
+                threshold = args.next().and_then(|t| t.parse().ok()).unwrap_or(f64::NAN);
+            }
+            "--input" => match args.next().as_deref() {
+                Some("puzzle") => input = InputSet::Puzzle,
+                Some(stem) => input = InputSet::Fixture(stem.to_string()),
+                None => samples = 0,
+            },
+            _ => {
+                eprintln!("unexpected argument: {arg}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if samples < bench::MIN_SAMPLES || threshold.is_nan() {
+        eprintln!(
+            "usage: aoc bench [--samples N>=2] [--input puzzle|FIXTURE] [--compare [--threshold PCT]]"
+        );
+        return ExitCode::FAILURE;
+    }
+
+    let history = bench::History::default();
+    let previous = match history.load() {
+        Ok(previous) => previous,
+        Err(e) => {
+            eprintln!("can't read {}: {e}", history.path().display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let (commit, machine) = (bench::current_commit(), bench::machine());
+    println!("Benchmarking {commit} on {machine}\n");
+
+    let mut records = vec![];
+    let mut regressions = 0;
+    for solver in SOLVERS {
+        let path = input.path(solver.day);
+        let Ok(contents) = fs::read_to_string(&path) else {
+            eprintln!("{solver}: can't read {}", path.display());
+            continue;
+        };
+        let Some(stats) = bench::measure(solver, &contents, samples) else {
+            eprintln!("{solver}: FAILED");
+            continue;
+        };
+        let record = bench::Record {
+            commit: commit.clone(),
+            machine: machine.clone(),
+            day: solver.day,
+            part: solver.part,
+            variant: solver.variant.unwrap_or("default").to_string(),
+            stats,
+        };
+
+        print!(
+            "{solver}: {:?} ± {:?}",
+            Duration::from_secs_f64(stats.mean / 1e9),
+            Duration::from_secs_f64(stats.stddev / 1e9)
+        );
+        if let Some(baseline) = bench::History::baseline(&previous, &record).filter(|_| compare) {
+            let comparison = stats.compare(&baseline.stats);
+            print!(" ({:+.1}% vs {}", comparison.change, baseline.commit);
+            if comparison.is_regression(threshold) {
+                regressions += 1;
+                print!(", REGRESSION");
+            } else if comparison.significant {
+                print!(", significant");
+            } else if comparison.insufficient {
+                print!(", insufficient samples");
+            }
+            print!(")");
+        }
+        println!();
+        records.push(record);
+    }
+
+    if let Err(e) = history.append(&records) {
+        eprintln!("can't write {}: {e}", history.path().display());
+        return ExitCode::FAILURE;
+    }
+    if regressions > 0 {
+        eprintln!("\n{regressions} solver(s) slowed down by more than {threshold}%");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+#[cfg(feature = "bench")]
+fn profile(mut args: impl Iterator<Item = String>) -> ExitCode {
+    let mut day = None;
+    let mut rounds = profile::DEFAULT_ROUNDS;
+    let mut samples = profile::DEFAULT_SAMPLES;
+    let mut compare = false;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--compare" => compare = true,
+            "--day" => day = Some(args.next().and_then(|d| d.parse().ok()).unwrap_or(0)),
+            "--rounds" => rounds = args.next().and_then(|n| n.parse().ok()).unwrap_or(0),
+            "--samples" => samples = args.next().and_then(|n| n.parse().ok()).unwrap_or(0),
+            _ => {
+                eprintln!("unexpected argument: {arg}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if rounds < 3 || samples == 0 || day == Some(0) {
+        eprintln!("usage: aoc profile [--day N] [--rounds N>=3] [--samples N] [--compare]");
+        return ExitCode::FAILURE;
+    }
+
+    let history = profile::History::default();
+    let previous = match history.load() {
+        Ok(previous) => previous,
+        Err(e) => {
+            eprintln!("can't read {}: {e}", history.path().display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut records = vec![];
+    let mut worsened = 0;
+    for solver in SOLVERS.iter().filter(|s| day.is_none_or(|d| d == s.day)) {
+        let Some(fit) = profile::profile(solver, rounds, samples, u64::from(solver.day)) else {
+            eprintln!("{solver}: no random input, or the solver failed on one");
+            continue;
+        };
+        let record = profile::record(solver, &fit);
+        let (first, last) = (&fit.samples[0], &fit.samples[fit.samples.len() - 1]);
+        print!(
+            "{solver}: {} ({} {} in {:?} .. {} in {:?}, spread {:.2}",
+            fit.class,
+            first.size,
+            profile::unit(solver.day),
+            first.time,
+            last.size,
+            last.time,
+            fit.spread
+        );
+        if let Some(before) = profile::History::previous(&previous, &record).filter(|_| compare) {
+            if record.class > before.class {
+                worsened += 1;
+                print!(", WORSE than {} at {}", before.class, before.commit);
+            } else if record.class < before.class {
+                print!(", better than {} at {}", before.class, before.commit);
+            }
+        }
+        println!(")");
+        records.push(record);
+    }
+
+    if let Err(e) = history.append(&records) {
+        eprintln!("can't write {}: {e}", history.path().display());
+        return ExitCode::FAILURE;
+    }
+    if worsened > 0 {
+        eprintln!("\n{worsened} solver(s) scale worse than before");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn dashboard(mut args: impl Iterator<Item = String>) -> ExitCode {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        if !run_option(&mut options, &arg, &mut args) {
+            eprintln!("unexpected argument: {arg}");
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let mut dashboard = aoc::dashboard::Dashboard::new(options);
+    let mut stdout = io::stdout();
+    let shown = if stdout.is_terminal() {
+        dashboard.interact(io::stdin().lock(), &mut stdout)
+    } else {
+        dashboard.plain(&mut stdout)
+    };
+    match shown {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("dashboard: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(feature = "viz")]
+fn visualize(mut args: impl Iterator<Item = String>) -> ExitCode {
+    let mut day = None;
+    let mut input = None;
+    let mut dump = None;
+    let mut player = aoc::viz::Player {
+        ansi: io::stdout().is_terminal(),
+        ..aoc::viz::Player::default()
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--speed" => player.fps = args.next().and_then(|f| f.parse().ok()).unwrap_or(0.0),
+            "--input" => input = args.next().map(PathBuf::from),
+            "--dump" => dump = args.next().map(PathBuf::from),
+            "--no-color" => player.ansi = false,
+            _ if day.is_none() && arg.parse::<u8>().is_ok() => day = arg.parse().ok(),
+            _ => {
+                eprintln!("unexpected argument: {arg}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(day) = day.filter(|d| aoc::viz::DAYS.contains(d)) else {
+        eprintln!(
+            "usage: aoc --visualize DAY [--input PATH] [--speed FPS] [--no-color] [--dump DIR]\n\
+             days with visualizations: {:?}",
+            aoc::viz::DAYS
+        );
+        return ExitCode::FAILURE;
+    };
+    if player.fps <= 0.0 {
+        eprintln!("--speed needs a positive number of frames per second");
+        return ExitCode::FAILURE;
+    }
+
+    let path = input.unwrap_or_else(|| run::input_path(day));
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("can't read {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let frames = match aoc::viz::frames(day, &contents) {
+        Ok(frames) => frames.unwrap_or_default(),
+        Err(e) => {
+            eprintln!("can't visualize {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    let shown = match dump {
+        Some(dir) => {
+            aoc::viz::dump(&frames, &dir).map(|n| println!("wrote {n} frames to {}", dir.display()))
+        }
+        None => player.play(&frames, &mut io::stdout()),
+    };
+    match shown {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("visualize: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn watch(mut args: impl Iterator<Item = String>) -> ExitCode {
+    let mut day = None;
+    let mut input = None;
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = args.next().and_then(|d| d.parse().ok()),
+            "--input" => input = args.next().map(PathBuf::from),
+            _ if run_option(&mut options, &arg, &mut args) => {}
+            _ => {
+                eprintln!("unexpected argument: {arg}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(day) = day.filter(|&d| SOLVERS.iter().any(|s| s.day == d)) else {
+        eprintln!(
+            "usage: aoc watch --day N [--input PATH] [--cache] [--timeout SECS] [--progress]"
+        );
+        return ExitCode::FAILURE;
+    };
+
+    let (default_input, fixtures) = aoc::watch::default_paths(day);
+    let watch = Watch::new(day, input.unwrap_or(default_input), fixtures).with_options(options);
+    match watch.run(&mut io::stdout()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("watch: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn lint(mut args: impl Iterator<Item = String>) -> ExitCode {
+    let mut day = None;
+    let mut input = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(args.next().and_then(|d| d.parse().ok()).unwrap_or(0)),
+            "--input" => input = args.next().map(PathBuf::from),
+            _ => {
+                eprintln!("unexpected argument: {arg}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let mut days: Vec<u8> = SOLVERS.iter().map(|s| s.day).collect();
+    days.dedup();
+    if day.is_some_and(|day| !days.contains(&day)) || (input.is_some() && day.is_none()) {
+        eprintln!("usage: aoc lint [--day N [--input PATH]]");
+        return ExitCode::FAILURE;
+    }
+
+    let mut problems = 0;
+    for day in days.into_iter().filter(|d| day.is_none_or(|day| day == *d)) {
+        let path = input.clone().unwrap_or_else(|| run::input_path(day));
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                problems += 1;
+                continue;
+            }
+        };
+        for diagnostic in aoc::lint::lint(day, &contents) {
+            problems += 1;
+            if diagnostic.line == 0 {
+                println!("{}: {}", path.display(), diagnostic.message);
+            } else {
+                println!(
+                    "{}:{}: {}",
+                    path.display(),
+                    diagnostic.line,
+                    diagnostic.message
+                );
+            }
+        }
+    }
+    if problems > 0 {
+        eprintln!("{problems} problem(s) found");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn detect(args: impl Iterator<Item = String>) -> ExitCode {
+    let paths: Vec<_> = args.map(PathBuf::from).collect();
+    if paths.is_empty() {
+        eprintln!("usage: aoc detect PATH...");
+        return ExitCode::FAILURE;
+    }
+
+    let mut status = ExitCode::SUCCESS;
+    for path in paths {
+        let guesses = match fs::read_to_string(&path) {
+            Ok(input) => aoc::detect::detect(&input),
+            Err(e) => {
+                eprintln!("{}: {e}", path.display());
+                status = ExitCode::FAILURE;
+                continue;
+            }
+        };
+        if guesses.is_empty() {
+            println!("{}: doesn't look like any day", path.display());
+        } else {
+            let guesses: Vec<_> = guesses.iter().map(ToString::to_string).collect();
+            println!("{}: {}", path.display(), guesses.join(", "));
+        }
+    }
+    status
+}
+
+fn repl(mut args: impl Iterator<Item = String>) -> ExitCode {
+    let mut day = None;
+    let mut input = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = args.next().and_then(|d| d.parse().ok()),
+            "--input" => input = args.next().map(PathBuf::from),
+            _ => {
+                eprintln!("unexpected argument: {arg}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(day) = day.filter(|&d| SOLVERS.iter().any(|s| s.day == d)) else {
+        eprintln!("usage: aoc repl --day N [--input PATH]");
+        return ExitCode::FAILURE;
+    };
+
+    let path = input.unwrap_or_else(|| run::input_path(day));
+    let contents = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            eprintln!("can't read {}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(warning) = aoc::detect::mismatch(day, &contents) {
+        eprintln!("warning: {warning}");
+    }
+    let history_path = PathBuf::from(repl::HISTORY_PATH);
+    let history = repl::load_history(&history_path).unwrap_or_else(|e| {
+        eprintln!("can't read {}: {e}", history_path.display());
+        vec![]
+    });
+    let mut repl = match Repl::new(day, &contents) {
+        Ok(repl) => repl.with_history(history),
+        Err(e) => {
+            eprintln!("{}: {e}", path.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let stdin = io::stdin();
+    let prompt = stdin.is_terminal();
+    if prompt {
+        println!(
+            "day {day}, {}: type `help` for the commands",
+            path.display()
+        );
+    }
+    let result = repl.run(stdin.lock(), &mut io::stdout(), prompt);
+    if let Err(e) = repl::save_history(&history_path, repl.history()) {
+        eprintln!("can't save {}: {e}", history_path.display());
+    }
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("repl: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn minimize(mut args: impl Iterator<Item = String>) -> ExitCode {
+    let mut day = None;
+    let mut part = None;
+    let mut variant = None;
+    let mut input = None;
+    let mut timeout = minimize::DEFAULT_TIMEOUT;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = args.next().and_then(|d| d.parse().ok()),
+            "--part" => part = args.next().and_then(|p| p.parse().ok()),
+            "--fails" => variant = args.next(),
+            "--timeout" => {
+                timeout = args
+                    .next()
+                    .and_then(|secs| secs.parse().ok())
+                    .map_or(Duration::ZERO, Duration::from_secs_f64);
+            }
+            "--input" => input = args.next().map(PathBuf::from),
+            _ => {
+                eprintln!("unexpected argument: {arg}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let (Some(day), Some(part), Some(input)) = (day, part, input) else {
+        eprintln!(
+            "usage: aoc minimize --day N --part N --input PATH [--fails VARIANT] [--timeout SECS]"
+        );
+        return ExitCode::FAILURE;
+    };
+    if timeout.is_zero() {
+        eprintln!("--timeout needs a positive number of seconds");
+        return ExitCode::FAILURE;
+    }
+
+    let input = match fs::read_to_string(&input) {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("can't read {}: {e}", input.display());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Without `--fails`, look for the variants disagreeing with each other.
+    let check = match variant.as_deref() {
+        None => Check::disagree(day, part, timeout),
+        Some(variant) => {
+            let variant = Some(variant).filter(|v| *v != "default");
+            let Some(solver) = aoc::registry::find(day, part, variant) else {
+                eprintln!("no such solver");
+                return ExitCode::FAILURE;
+            };
+            Check::fails(solver, &input, timeout)
+        }
+    };
+    let check = match check {
+        Ok(check) => check,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if !check.reproduces(&input) {
+        eprintln!("the input doesn't reproduce the failure");
+        return ExitCode::FAILURE;
+    }
+
+    let minimized = minimize::minimize(day, &input, |s| check.reproduces(s));
+    match minimize::save(day, &minimized) {
+        Ok(path) => {
+            println!(
+                "reduced {} lines to {}, saved to {}",
+                input.lines().count(),
+                minimized.lines().count(),
+                path.display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("can't save the reproducer: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn report(mut args: impl Iterator<Item = String>) -> ExitCode {
+    let mut dir = None;
+    let mut input = InputSet::Puzzle;
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--html" => dir = args.next().map(PathBuf::from),
+            "--input" => match args.next().as_deref() {
+                Some("puzzle") => input = InputSet::Puzzle,
+                Some(stem) => input = InputSet::Fixture(stem.to_string()),
+                None => dir = None,
+            },
+            _ if run_option(&mut options, &arg, &mut args) => {}
+            _ => {
+                eprintln!("unexpected argument: {arg}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    let Some(dir) = dir else {
+        eprintln!(
+            "usage: aoc report --html DIR [--input puzzle|FIXTURE] [--cache] [--timeout SECS] [--progress]"
+        );
+        return ExitCode::FAILURE;
+    };
+
+    let report = aoc::report::Report::collect(input, &options);
+    match aoc::report::save(&report, &dir) {
+        Ok(path) => {
+            println!("wrote {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("can't write the report: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(feature = "serve")]
+fn serve(mut args: impl Iterator<Item = String>) -> ExitCode {
+    let addr = args
+        .next()
+        .unwrap_or_else(|| aoc::serve::DEFAULT_ADDR.to_string());
+    let server = match aoc::serve::Server::bind(&addr) {
+        Ok(server) => server,
+        Err(e) => {
+            eprintln!("can't bind {addr}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Ok(addr) = server.local_addr() {
+        println!("listening on http://{addr}");
+    }
+    if let Err(e) = server.serve() {
+        eprintln!("serve: {e}");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,309 @@
+use crate::cache;
+use crate::registry::{Solver, SOLVERS};
+use crate::run::{self, Failure, Options};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// How long a solver can run on a candidate before it counts as hanging.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How a day's input breaks down into pieces that can be removed independently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Structure {
+    /// One item per line (days 1, 2, 3 and 5).
+    Lines,
+    /// A draw line that's always kept, followed by blank-line separated boards (day 4).
+    Boards,
+    /// A single comma-separated list (days 6 and 7).
+    CommaList,
+}
+
+/// What makes an input interesting enough to keep while minimizing.
+///
+/// Candidates are run with a timeout, so an input that makes a solver hang can be minimized too.
+#[derive(Clone)]
+pub enum Check {
+    /// The variants for a day and part don't all give the same answer.
+    Disagree {
+        day: u8,
+        part: u8,
+        timeout: Duration,
+    },
+    /// A solver fails in the same stage and with the same message as it did on the original input.
+    Fails {
+        solver: &'static Solver,
+        failure: Failure,
+        timeout: Duration,
+    },
+}
+
+#[must_use]
+pub fn structure(day: u8) -> Structure {
+    match day {
+        4 => Structure::Boards,
+        6 | 7 => Structure::CommaList,
+        _ => Structure::Lines,
+    }
+}
+
+impl Check {
+    /// Checks for the variants of `day` and `part` disagreeing.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there aren't at least two variants to disagree.
+    pub fn disagree(day: u8, part: u8, timeout: Duration) -> Result<Self, String> {
+        let variants = SOLVERS
+            .iter()
+            .filter(|s| s.day == day && s.part == part)
+            .count();
+        if variants < 2 {
+            return Err(format!(
+                "day {day} part {part} has {variants} variant(s), so they can't disagree"
+            ));
+        }
+        Ok(Check::Disagree { day, part, timeout })
+    }
+
+    /// Checks for `solver` failing the way it fails on `input`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `solver` doesn't fail on `input`.
+    pub fn fails(solver: &'static Solver, input: &str, timeout: Duration) -> Result<Self, String> {
+        match run::run_with(solver, input, &options(timeout)).answer {
+            Ok(_) => Err(format!("{solver} doesn't fail on the input")),
+            Err(failure) => Ok(Check::Fails {
+                solver,
+                failure,
+                timeout,
+            }),
+        }
+    }
+
+    #[must_use]
+    pub fn day(&self) -> u8 {
+        match self {
+            Check::Disagree { day, .. } => *day,
+            Check::Fails { solver, .. } => solver.day,
+        }
+    }
+
+    #[must_use]
+    pub fn reproduces(&self, input: &str) -> bool {
+        match self {
+            Check::Disagree { day, part, timeout } => {
+                let mut answers = SOLVERS
+                    .iter()
+                    .filter(|s| s.day == *day && s.part == *part)
+                    .map(|s| run::run_with(s, input, &options(*timeout)).answer);
+                answers
+                    .next()
+                    .is_some_and(|first| answers.any(|a| a != first))
+            }
+            Check::Fails {
+                solver,
+                failure,
+                timeout,
+            } => {
+                run::run_with(solver, input, &options(*timeout))
+                    .answer
+                    .as_ref()
+                    == Err(failure)
+            }
+        }
+    }
+}
+
+fn options(timeout: Duration) -> Options {
+    Options {
+        timeout: Some(timeout),
+        ..Options::default()
+    }
+}
+
+/// Shrinks `input` to a smaller one for which `reproduces` still holds, removing whole items of
+/// the day's [`Structure`] using delta debugging.
+///
+/// `reproduces` must hold for `input` itself; if it doesn't, `input` is returned unchanged.
+pub fn minimize(day: u8, input: &str, mut reproduces: impl FnMut(&str) -> bool) -> String {
+    let input = input.trim_end_matches('\n');
+    let (header, units, separator) = split(structure(day), input);
+    let join = |units: &[&str]| {
+        let body = units.join(separator);
+        match header {
+            Some(header) if body.is_empty() => header.to_string(),
+            Some(header) => format!("{header}{separator}{body}"),
+            None => body,
+        }
+    };
+
+    if !reproduces(input) {
+        return input.to_string();
+    }
+
+    let mut units = units;
+    let mut chunks = 2;
+    while !units.is_empty() {
+        chunks = chunks.min(units.len());
+        let size = units.len().div_ceil(chunks);
+
+        let smaller = (0..units.len()).step_by(size).find_map(|start| {
+            let complement: Vec<_> = units[..start]
+                .iter()
+                .chain(units.get(start + size..).unwrap_or_default())
+                .copied()
+                .collect();
+            reproduces(&join(&complement)).then_some(complement)
+        });
+
+        match smaller {
+            Some(smaller) => {
+                units = smaller;
+                chunks = (chunks - 1).max(2);
+            }
+            None if chunks == units.len() => break,
+            None => chunks *= 2,
+        }
+    }
+
+    join(&units)
+}
+
+fn split(structure: Structure, input: &str) -> (Option<&str>, Vec<&str>, &'static str) {
+    match structure {
+        Structure::Lines => (None, input.lines().collect(), "\n"),
+        Structure::Boards => {
+            let mut blocks = input.split("\n\n");
+            let header = blocks.next();
+            (header, blocks.collect(), "\n\n")
+        }
+        Structure::CommaList => (None, input.split(',').collect(), ","),
+    }
+}
+
+/// Saves a reproducer into the day's fixture corpus, named after a hash of its contents.
+///
+/// # Errors
+///
+/// Returns an error if the fixture can't be written.
+pub fn save(day: u8, input: &str) -> io::Result<PathBuf> {
+    let dir = run::fixture_dir(day);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("repro-{:016x}.txt", cache::fnv1a(input.as_bytes())));
+    fs::write(&path, format!("{input}\n"))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn lines() {
+        let input = (1..=50)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let minimized = super::minimize(1, &input, |s| {
+            s.lines().any(|l| l == "7") && s.lines().any(|l| l == "42")
+        });
+        assert_eq!(minimized, "7\n42");
+    }
+
+    #[test]
+    fn comma_list() {
+        let minimized = super::minimize(6, "3,4,3,1,2", |s| s.contains('1'));
+        assert_eq!(minimized, "1");
+    }
+
+    #[test]
+    #[cfg(feature = "day4")]
+    fn boards() {
+        use super::{Check, DEFAULT_TIMEOUT};
+        use crate::registry;
+
+        let input = std::fs::read_to_string("fixtures/2021/day4/example.txt").unwrap();
+        let draws = input.lines().next().unwrap();
+
+        // Only the third board contains 26.
+        let minimized = super::minimize(4, &input, |s| {
+            s.split("\n\n").skip(1).any(|b| b.contains("26"))
+        });
+        assert_eq!(
+            minimized,
+            format!(
+                "{draws}\n\n14 21 17 24  4\n10 16 15  9 19\n18  8 23 26 20\n22 11 13  6  5\n 2  0 12  3  7"
+            )
+        );
+
+        // Only the bad board makes the generator fail. Without it part 1 succeeds, and without the
+        // other boards too it fails differently, with no winner.
+        let part1 = registry::find(4, 1, None).unwrap();
+        let bad = "1 2 3 4 5\n6 7 8 9 10\n11 12 x 14 15\n16 17 18 19 20\n21 22 23 24 25";
+        let input = format!("{}\n\n{bad}", input.trim_end());
+        let check = Check::fails(part1, &input, DEFAULT_TIMEOUT).unwrap();
+        let minimized = super::minimize(4, &input, |s| check.reproduces(s));
+        assert_eq!(minimized, format!("{draws}\n\n{bad}"));
+        assert!(!check.reproduces(draws));
+    }
+
+    #[test]
+    #[cfg(feature = "day3")]
+    fn hangs() {
+        use super::Check;
+        use crate::registry;
+        use std::time::Duration;
+
+        // Duplicate rows keep day 3's winnowing going forever.
+        let part2 = registry::find(3, 2, None).unwrap();
+        let input = "101\n101\n101\n101";
+        let check = Check::fails(part2, input, Duration::from_millis(50)).unwrap();
+        assert_eq!(
+            super::minimize(3, input, |s| check.reproduces(s)),
+            "101\n101"
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day5", feature = "day7"))]
+    fn checks() {
+        use super::{Check, DEFAULT_TIMEOUT};
+        use crate::registry;
+
+        assert!(Check::disagree(5, 1, DEFAULT_TIMEOUT).is_err());
+        assert!(Check::disagree(1, 1, DEFAULT_TIMEOUT).is_ok());
+        let part1 = registry::find(7, 1, None).unwrap();
+        assert!(Check::fails(part1, "1,2,3", DEFAULT_TIMEOUT).is_err());
+    }
+
+    #[test]
+    fn not_reproducible() {
+        assert_eq!(super::minimize(7, "1,2,3\n", |_| false), "1,2,3");
+    }
+
+    // Differential test: every day 1 variant should agree on arbitrary depths.
+    #[test]
+    #[cfg(feature = "day1")]
+    fn day1_variants_agree() {
+        use super::{Check, DEFAULT_TIMEOUT};
+        use crate::random::Rng;
+
+        let mut rng = Rng::new(31);
+        for _ in 0..50 {
+            let len = rng.range(3..=200);
+            let input = (0..len)
+                .map(|_| rng.range(0..=10_000).to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+            for part in 1..=2 {
+                let check = Check::disagree(1, part, DEFAULT_TIMEOUT).unwrap();
+                assert!(
+                    !check.reproduces(&input),
+                    "{}",
+                    super::minimize(1, &input, |s| check.reproduces(s))
+                );
+            }
+        }
+    }
+}
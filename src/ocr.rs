@@ -0,0 +1,202 @@
+use crate::grid::Grid;
+use std::error::Error;
+use std::fmt;
+
+/// The letters drawn 4 pixels wide and 6 tall, as in 2021 day 13. `I` and `Y` are narrower and
+/// wider than the rest.
+#[rustfmt::skip]
+const SMALL: &[(char, [&str; 6])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', ["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// The letters drawn 6 pixels wide and 10 tall, as in 2018 day 10.
+#[rustfmt::skip]
+const LARGE: &[(char, [&str; 10])] = &[
+    ('A', ["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', ["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', [".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', ["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', [".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', ["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', ["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', ["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', ["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', ["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', ["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', ["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', ["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', ["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+/// Some glyphs in a picture didn't match any letter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unrecognized {
+    /// What could be read, with `?` for each glyph that couldn't.
+    pub text: String,
+    /// Each unrecognized glyph with the column it starts at.
+    pub glyphs: Vec<(usize, Grid)>,
+}
+
+impl fmt::Display for Unrecognized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "couldn't read every letter of {:?}", self.text)?;
+        for (column, glyph) in &self.glyphs {
+            write!(f, "\n\nat column {column}:\n{glyph}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for Unrecognized {}
+
+/// Reads the letters drawn in `pixels`.
+///
+/// Letters are told apart by the blank columns between them, and the font is picked by the
+/// height of what's drawn, so blank rows and columns around the letters don't matter.
+///
+/// # Errors
+///
+/// Returns [`Unrecognized`] if any glyph isn't a letter in the font, including when the letters
+/// aren't as tall as either font's.
+pub fn read(pixels: &Grid) -> Result<String, Unrecognized> {
+    let lit = |row: &[bool]| row.iter().any(|&p| p);
+    let top = pixels.rows().position(lit).unwrap_or(0);
+    let bottom = pixels.rows().rposition(lit).map_or(0, |b| b + 1);
+    let height = bottom.saturating_sub(top);
+    let width = pixels.width();
+    let pixel = |row: usize, column: usize| pixels[(column, top + row)];
+    let blank = |column: usize| (0..height).all(|row| !pixel(row, column));
+
+    let mut text = String::new();
+    let mut glyphs = vec![];
+    let mut column = 0;
+    while column < width {
+        if blank(column) {
+            column += 1;
+            continue;
+        }
+        let start = column;
+        while column < width && !blank(column) {
+            column += 1;
+        }
+        let mut glyph = Grid::new(column - start, height, false);
+        for row in 0..height {
+            for c in start..column {
+                glyph[(c - start, row)] = pixel(row, c);
+            }
+        }
+        if let Some(letter) = letter(&glyph) {
+            text.push(letter);
+        } else {
+            text.push('?');
+            glyphs.push((start, glyph));
+        }
+    }
+
+    if glyphs.is_empty() {
+        Ok(text)
+    } else {
+        Err(Unrecognized { text, glyphs })
+    }
+}
+
+/// Like [`read`], for a picture drawn as text with `#` for lit pixels. Any other character is
+/// unlit.
+///
+/// # Errors
+///
+/// Returns [`Unrecognized`] if any glyph isn't a letter in the font.
+pub fn read_text(picture: &str) -> Result<String, Unrecognized> {
+    read(&Grid::from_text(picture))
+}
+
+fn letter(glyph: &Grid) -> Option<char> {
+    let matches = |rows: &[&str]| {
+        rows.iter().zip(glyph.rows()).all(|(expected, row)| {
+            expected.len() == row.len() && expected.chars().zip(row).all(|(c, &p)| (c == '#') == p)
+        })
+    };
+    match glyph.height() {
+        6 => SMALL
+            .iter()
+            .find(|(_, rows)| matches(rows))
+            .map(|&(c, _)| c),
+        10 => LARGE
+            .iter()
+            .find(|(_, rows)| matches(rows))
+            .map(|&(c, _)| c),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Unrecognized, LARGE, SMALL};
+    use crate::grid::Grid;
+
+    // Draws `glyphs` side by side with `gap` blank columns between them.
+    fn draw(glyphs: &[&[&str]], gap: usize) -> String {
+        let height = glyphs.iter().map(|g| g.len()).max().unwrap();
+        (0..height)
+            .map(|row| {
+                let row: Vec<_> = glyphs.iter().map(|g| g[row]).collect();
+                row.join(&".".repeat(gap))
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn small() {
+        let glyphs: Vec<&[&str]> = SMALL.iter().map(|(_, rows)| &rows[..]).collect();
+        let letters: String = SMALL.iter().map(|&(c, _)| c).collect();
+        assert_eq!(super::read_text(&draw(&glyphs, 1)).unwrap(), letters);
+    }
+
+    #[test]
+    fn large() {
+        let glyphs: Vec<&[&str]> = LARGE.iter().map(|(_, rows)| &rows[..]).collect();
+        let letters: String = LARGE.iter().map(|&(c, _)| c).collect();
+        assert_eq!(super::read_text(&draw(&glyphs, 2)).unwrap(), letters);
+    }
+
+    #[test]
+    fn read() {
+        // The 2021 day 13 example, which draws a square rather than a letter.
+        let square = "#####\n#...#\n#...#\n#...#\n#####\n.....\n.....";
+        assert_eq!(
+            super::read_text(square),
+            Err(Unrecognized {
+                text: "?".to_string(),
+                glyphs: vec![(0, Grid::from_text("#####\n#...#\n#...#\n#...#\n#####"))],
+            })
+        );
+
+        let pixels = Grid::from_text(
+            "..........\n.#..#.###.\n.#..#.#..#\n.####.###.\n.#..#.#..#\n.#..#.#..#\n.#..#.###.",
+        );
+        assert_eq!(super::read(&pixels).unwrap(), "HB");
+
+        let error = super::read_text(&draw(&[&SMALL[0].1, &["#", "#", "#", "#", "#", "#"]], 1));
+        assert_eq!(error.unwrap_err().text, "A?");
+        assert_eq!(super::read_text(""), Ok(String::new()));
+    }
+}
@@ -0,0 +1,378 @@
+use crate::bench;
+use crate::random::{self, Rng};
+use crate::registry::Solver;
+use crate::run;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const DEFAULT_ROUNDS: u32 = 6;
+pub const DEFAULT_SAMPLES: usize = 3;
+
+/// Candidate complexity classes, from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Complexity {
+    Constant,
+    Logarithmic,
+    Linear,
+    Linearithmic,
+    Quadratic,
+    Cubic,
+}
+
+/// One input size and how long the solver took on it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    pub size: u64,
+    pub time: Duration,
+}
+
+/// The best-fitting class for a solver, with the samples it was fitted to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fit {
+    pub class: Complexity,
+    /// How far the samples stray from the fitted curve, as a coefficient of variation. Anything
+    /// much above 0.3 means the fit shouldn't be trusted.
+    pub spread: f64,
+    pub samples: Vec<Sample>,
+}
+
+/// One line of the profile history file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub commit: String,
+    pub day: u8,
+    pub part: u8,
+    pub variant: String,
+    pub class: Complexity,
+}
+
+/// An append-only file of fitted classes, one tab-separated [`Record`] per line.
+#[derive(Debug, Clone)]
+pub struct History {
+    path: PathBuf,
+}
+
+impl Complexity {
+    pub const ALL: [Complexity; 6] = [
+        Complexity::Constant,
+        Complexity::Logarithmic,
+        Complexity::Linear,
+        Complexity::Linearithmic,
+        Complexity::Quadratic,
+        Complexity::Cubic,
+    ];
+
+    #[must_use]
+    pub fn eval(self, n: f64) -> f64 {
+        let n = n.max(2.0);
+        match self {
+            Complexity::Constant => 1.0,
+            Complexity::Logarithmic => n.ln(),
+            Complexity::Linear => n,
+            Complexity::Linearithmic => n * n.ln(),
+            Complexity::Quadratic => n * n,
+            Complexity::Cubic => n * n * n,
+        }
+    }
+}
+
+impl fmt::Display for Complexity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Complexity::Constant => "O(1)",
+            Complexity::Logarithmic => "O(log n)",
+            Complexity::Linear => "O(n)",
+            Complexity::Linearithmic => "O(n log n)",
+            Complexity::Quadratic => "O(n^2)",
+            Complexity::Cubic => "O(n^3)",
+        })
+    }
+}
+
+/// What `n` means for a day's solvers.
+///
+/// Most days count input items, but day 5 is measured in points covered by its segments and day 7
+/// in crabs times the range of positions, since that's what their solvers loop over.
+#[must_use]
+pub fn unit(day: u8) -> &'static str {
+    match day {
+        5 => "segment points",
+        7 => "crabs x range",
+        _ => "items",
+    }
+}
+
+/// Measures `input` in the [`unit`] for `day`.
+#[must_use]
+pub fn size(day: u8, input: &str) -> u64 {
+    let numbers = |s: &str| -> Vec<u64> { s.split(',').filter_map(|n| n.parse().ok()).collect() };
+    match day {
+        4 => input.split("\n\n").skip(1).count() as u64,
+        5 => input
+            .lines()
+            .filter_map(|line| {
+                let (start, end) = line.split_once(" -> ")?;
+                let (start, end) = (numbers(start), numbers(end));
+                let dx = start.first()?.abs_diff(*end.first()?);
+                let dy = start.get(1)?.abs_diff(*end.get(1)?);
+                Some(dx.max(dy) + 1)
+            })
+            .sum(),
+        6 => numbers(input).len() as u64,
+        7 => {
+            let crabs = numbers(input);
+            let range = crabs.iter().max().unwrap_or(&0) - crabs.iter().min().unwrap_or(&0) + 1;
+            crabs.len() as u64 * range
+        }
+        _ => input.lines().count() as u64,
+    }
+}
+
+/// The number of items the first round starts from, so every day's rounds take a similar time.
+#[must_use]
+pub fn base_size(day: u8) -> usize {
+    match day {
+        3 => 1024,
+        4 | 5 | 7 => 50,
+        6 => 1000,
+        _ => 10_000,
+    }
+}
+
+/// Times `solver` on random inputs that double in size each round, and fits the timings.
+///
+/// Returns `None` if the day has no random input generator or the solver fails on one.
+#[must_use]
+pub fn profile(solver: &Solver, rounds: u32, samples: usize, seed: u64) -> Option<Fit> {
+    let mut rng = Rng::new(seed);
+    let mut points = vec![];
+    for round in 0..rounds {
+        let input = random::input(solver.day, base_size(solver.day) << round, &mut rng)?;
+        let mut best = Duration::MAX;
+        for _ in 0..samples.max(1) {
+            let run = run::run(solver, &input);
+            run.answer.ok()?;
+            best = best.min(run.solver);
+        }
+        points.push(Sample {
+            size: size(solver.day, &input),
+            time: best,
+        });
+    }
+    Some(fit(points))
+}
+
+/// Picks the class that best explains `samples`: the one for which time divided by the class's
+/// growth is most nearly constant.
+///
+/// Ties go to the simpler class.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn fit(samples: Vec<Sample>) -> Fit {
+    let spread = |class: Complexity| {
+        let ratios: Vec<_> = samples
+            .iter()
+            .map(|s| s.time.as_secs_f64() / class.eval(s.size as f64))
+            .collect();
+        let n = ratios.len() as f64;
+        let mean = ratios.iter().sum::<f64>() / n;
+        let variance = ratios.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n;
+        if mean > 0.0 {
+            variance.sqrt() / mean
+        } else {
+            f64::INFINITY
+        }
+    };
+    let (class, spread) = Complexity::ALL
+        .into_iter()
+        .map(|class| (class, spread(class)))
+        .fold((Complexity::Constant, f64::INFINITY), |best, next| {
+            if next.1 < best.1 {
+                next
+            } else {
+                best
+            }
+        });
+    Fit {
+        class,
+        spread,
+        samples,
+    }
+}
+
+impl Record {
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}",
+            self.commit, self.day, self.part, self.variant, self.class
+        )
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let mut next = || fields.next();
+        Some(Self {
+            commit: next()?.to_string(),
+            day: next()?.parse().ok()?,
+            part: next()?.parse().ok()?,
+            variant: next()?.to_string(),
+            class: next()
+                .and_then(|class| Complexity::ALL.into_iter().find(|c| c.to_string() == class))?,
+        })
+    }
+}
+
+impl History {
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every record so far, oldest first. Lines that don't parse are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file exists but can't be read.
+    pub fn load(&self) -> io::Result<Vec<Record>> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(contents.lines().filter_map(Record::parse).collect()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the history file can't be created or written.
+    pub fn append(&self, records: &[Record]) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        for record in records {
+            writeln!(file, "{}", record.to_line())?;
+        }
+        Ok(())
+    }
+
+    /// The class most recently recorded for the same solver as `record` at another commit.
+    #[must_use]
+    pub fn previous<'a>(records: &'a [Record], record: &Record) -> Option<&'a Record> {
+        records.iter().rev().find(|r| {
+            (r.day, r.part, r.variant.as_str())
+                == (record.day, record.part, record.variant.as_str())
+                && r.commit != record.commit
+        })
+    }
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new("target/aoc-profile/history.tsv")
+    }
+}
+
+/// Builds the history record for a fitted solver at the current commit.
+#[must_use]
+pub fn record(solver: &Solver, fit: &Fit) -> Record {
+    Record {
+        commit: bench::current_commit(),
+        day: solver.day,
+        part: solver.part,
+        variant: solver.variant.unwrap_or("default").to_string(),
+        class: fit.class,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Complexity, History, Record, Sample};
+    use std::time::Duration;
+
+    fn samples(f: impl Fn(f64) -> f64) -> Vec<Sample> {
+        (0..6)
+            .map(|i| {
+                let size = 1000 << i;
+                Sample {
+                    size,
+                    #[allow(clippy::cast_precision_loss)]
+                    time: Duration::from_secs_f64(f(size as f64) * 1e-9),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn fit() {
+        assert_eq!(super::fit(samples(|_| 500.0)).class, Complexity::Constant);
+        assert_eq!(
+            super::fit(samples(|n| 3.0 * n + 50.0)).class,
+            Complexity::Linear
+        );
+        assert_eq!(
+            super::fit(samples(|n| n * n.ln())).class,
+            Complexity::Linearithmic
+        );
+        assert_eq!(
+            super::fit(samples(|n| n * n / 100.0)).class,
+            Complexity::Quadratic
+        );
+    }
+
+    #[test]
+    fn size() {
+        assert_eq!(super::size(5, "0,9 -> 5,9\n8,0 -> 0,8"), 15);
+        assert_eq!(super::size(7, "16,1,2"), 48);
+        assert_eq!(super::size(1, "1\n2\n3"), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "day6")]
+    fn profile() {
+        use crate::registry;
+
+        let solver = registry::find(6, 2, None).unwrap();
+        let fit = super::profile(solver, 3, 1, 1).unwrap();
+        assert_eq!(fit.samples.len(), 3);
+        assert_eq!(fit.samples[2].size, 4000);
+    }
+
+    #[test]
+    fn history() {
+        let dir = std::env::temp_dir().join(format!("aoc-profile-{}", std::process::id()));
+        let history = History::new(dir.join("history.tsv"));
+        let record = |commit: &str, class| Record {
+            commit: commit.to_string(),
+            day: 7,
+            part: 1,
+            variant: "default".to_string(),
+            class,
+        };
+        history
+            .append(&[
+                record("a", Complexity::Linear),
+                record("b", Complexity::Linear),
+            ])
+            .unwrap();
+        let records = history.load().unwrap();
+        assert_eq!(records[1], record("b", Complexity::Linear));
+
+        let current = record("b", Complexity::Quadratic);
+        let previous = History::previous(&records, &current).unwrap();
+        assert_eq!(previous.commit, "a");
+        assert!(current.class > previous.class);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,344 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// How often a [`Bar`] is redrawn.
+pub const BAR_INTERVAL: Duration = Duration::from_millis(100);
+/// How often a [`Log`] writes a line.
+pub const LOG_INTERVAL: Duration = Duration::from_secs(5);
+
+const BAR_WIDTH: usize = 30;
+
+/// How far a solver has got with a task, as passed to a [`Sink`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Update<'a> {
+    /// The solver doing the work.
+    pub label: &'a str,
+    /// What's being counted, like `"days"`.
+    pub task: &'static str,
+    pub done: u64,
+    pub total: u64,
+    pub elapsed: Duration,
+    /// Whether this is the last update for the task.
+    pub finished: bool,
+}
+
+/// Shows progress to the user.
+///
+/// Tasks that finish within one [`Sink::interval`] are never reported, so quick solvers don't make
+/// any noise.
+pub trait Sink: Send + Sync {
+    /// The least time between two updates for the same task.
+    fn interval(&self) -> Duration;
+
+    fn report(&self, update: &Update<'_>);
+}
+
+/// Redraws one line in place, for a terminal.
+pub struct Bar<W>(Mutex<W>);
+
+/// Writes a line every so often, for when the output isn't a terminal.
+pub struct Log<W> {
+    out: Mutex<W>,
+    interval: Duration,
+}
+
+/// Where the solver running on this thread reports to, as installed by the runner.
+#[derive(Clone)]
+pub(crate) struct Reporter {
+    sink: Arc<dyn Sink>,
+    label: String,
+}
+
+/// A task a solver is working through, started with [`start`].
+///
+/// Without a [`Sink`] installed nothing is measured or reported, so this costs one check per call
+/// to [`Progress::advance`].
+pub struct Progress {
+    reporter: Option<Reporter>,
+    task: &'static str,
+    total: u64,
+    done: u64,
+    start: Instant,
+    last_report: Instant,
+    reported: bool,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Reporter>> = const { RefCell::new(None) };
+}
+
+pub(crate) fn set_current(sink: Option<Arc<dyn Sink>>, label: impl FnOnce() -> String) {
+    let reporter = sink.map(|sink| Reporter {
+        sink,
+        label: label(),
+    });
+    CURRENT.with(|c| *c.borrow_mut() = reporter);
+}
+
+/// Starts a task of `total` units of work, reporting to the sink the runner installed for this
+/// thread, if any. The task finishes when the returned [`Progress`] is dropped.
+#[must_use]
+pub fn start(task: &'static str, total: u64) -> Progress {
+    let reporter = CURRENT.with(|c| c.borrow().clone());
+    let now = Instant::now();
+    Progress {
+        reporter,
+        task,
+        total,
+        done: 0,
+        start: now,
+        last_report: now,
+        reported: false,
+    }
+}
+
+/// The sink to show progress on stderr with: a [`Bar`] on a terminal, otherwise a [`Log`].
+#[must_use]
+pub fn stderr() -> Arc<dyn Sink> {
+    if io::IsTerminal::is_terminal(&io::stderr()) {
+        Arc::new(Bar::new(io::stderr()))
+    } else {
+        Arc::new(Log::new(io::stderr(), LOG_INTERVAL))
+    }
+}
+
+impl Progress {
+    /// Records `units` more units of work done.
+    #[inline]
+    pub fn advance(&mut self, units: u64) {
+        if let Some(reporter) = &self.reporter {
+            self.done += units;
+            let now = Instant::now();
+            if now - self.last_report >= reporter.sink.interval() {
+                self.last_report = now;
+                self.reported = true;
+                self.report(reporter, false);
+            }
+        }
+    }
+
+    fn report(&self, reporter: &Reporter, finished: bool) {
+        reporter.sink.report(&Update {
+            label: &reporter.label,
+            task: self.task,
+            done: self.done,
+            total: self.total,
+            elapsed: self.start.elapsed(),
+            finished,
+        });
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        if let Some(reporter) = self.reporter.as_ref().filter(|_| self.reported) {
+            self.report(reporter, true);
+        }
+    }
+}
+
+impl Update<'_> {
+    /// How much of the task is done, from 0 to 1.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.done as f64 / self.total as f64).min(1.0)
+        }
+    }
+}
+
+impl<W: Write> Bar<W> {
+    pub fn new(out: W) -> Self {
+        Self(Mutex::new(out))
+    }
+}
+
+impl<W: Write + Send> Sink for Bar<W> {
+    fn interval(&self) -> Duration {
+        BAR_INTERVAL
+    }
+
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn report(&self, update: &Update<'_>) {
+        let mut out = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        // Progress is best effort, so failing to show it isn't an error.
+        let _ = if update.finished {
+            write!(out, "\r\x1b[2K")
+        } else {
+            let filled = (update.fraction() * BAR_WIDTH as f64) as usize;
+            write!(
+                out,
+                "\r{}: {} [{}{}] {:>3.0}% {}/{}",
+                update.label,
+                update.task,
+                "#".repeat(filled),
+                ".".repeat(BAR_WIDTH - filled),
+                update.fraction() * 100.0,
+                update.done,
+                update.total
+            )
+        };
+        let _ = out.flush();
+    }
+}
+
+impl<W: Write> Log<W> {
+    pub fn new(out: W, interval: Duration) -> Self {
+        Self {
+            out: Mutex::new(out),
+            interval,
+        }
+    }
+}
+
+impl<W: Write + Send> Sink for Log<W> {
+    fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    fn report(&self, update: &Update<'_>) {
+        let mut out = self.out.lock().unwrap_or_else(PoisonError::into_inner);
+        let _ = if update.finished {
+            writeln!(
+                out,
+                "{}: {} done after {:.1?}",
+                update.label, update.task, update.elapsed
+            )
+        } else {
+            writeln!(
+                out,
+                "{}: {} {}/{} ({:.0}%) after {:.1?}",
+                update.label,
+                update.task,
+                update.done,
+                update.total,
+                update.fraction() * 100.0,
+                update.elapsed
+            )
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Bar, Log, Sink, Update};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    // The label, task, units done, total and whether it's finished.
+    type Recorded = (String, &'static str, u64, u64, bool);
+
+    // Keeps every update, with no interval between them.
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<Recorded>>);
+
+    impl Sink for Recorder {
+        fn interval(&self) -> Duration {
+            Duration::ZERO
+        }
+
+        fn report(&self, u: &Update<'_>) {
+            let update = (u.label.to_string(), u.task, u.done, u.total, u.finished);
+            self.0.lock().unwrap().push(update);
+        }
+    }
+
+    fn update(done: u64, finished: bool) -> Update<'static> {
+        Update {
+            label: "Day 6 - Part 2",
+            task: "days",
+            done,
+            total: 256,
+            elapsed: Duration::from_millis(1500),
+            finished,
+        }
+    }
+
+    #[test]
+    fn start() {
+        // Nothing installed, so nothing is counted.
+        let mut progress = super::start("days", 10);
+        progress.advance(3);
+        assert_eq!(progress.done, 0);
+        drop(progress);
+
+        let recorder = Arc::new(Recorder::default());
+        super::set_current(Some(recorder.clone()), || "test".to_string());
+        {
+            let mut progress = super::start("days", 2);
+            progress.advance(1);
+            progress.advance(1);
+        }
+        // A task that never reported doesn't report finishing either.
+        drop(super::start("positions", 5));
+        super::set_current(None, String::new);
+
+        let updates = recorder.0.lock().unwrap().clone();
+        let test = || "test".to_string();
+        assert_eq!(
+            updates,
+            [
+                (test(), "days", 1, 2, false),
+                (test(), "days", 2, 2, false),
+                (test(), "days", 2, 2, true),
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "day5", feature = "day6", feature = "day7"))]
+    fn solvers() {
+        use crate::{registry, run};
+
+        for (day, part, task) in [(5, 1, "lines"), (6, 2, "days"), (7, 2, "positions")] {
+            let recorder = Arc::new(Recorder::default());
+            let options = run::Options {
+                progress: Some(recorder.clone()),
+                ..run::Options::default()
+            };
+            let solver = registry::find(day, part, None).unwrap();
+            let input = std::fs::read_to_string(run::fixture_dir(day).join("example.txt")).unwrap();
+            assert!(run::run_with(solver, &input, &options).answer.is_ok());
+
+            let updates = recorder.0.lock().unwrap();
+            let (label, reported, done, total, finished) = updates.last().unwrap();
+            assert_eq!(
+                (label.as_str(), *reported, done, *finished),
+                (solver.to_string().as_str(), task, total, true)
+            );
+        }
+    }
+
+    #[test]
+    fn bar() {
+        let bar = Bar::new(vec![]);
+        bar.report(&update(64, false));
+        bar.report(&update(256, true));
+        let out = String::from_utf8(bar.0.into_inner().unwrap()).unwrap();
+        assert_eq!(
+            out,
+            "\rDay 6 - Part 2: days [#######.......................]  25% 64/256\r\x1b[2K"
+        );
+    }
+
+    #[test]
+    fn log() {
+        let log = Log::new(vec![], Duration::from_secs(1));
+        log.report(&update(128, false));
+        log.report(&update(256, true));
+        let out = String::from_utf8(log.out.into_inner().unwrap()).unwrap();
+        assert_eq!(
+            out,
+            "Day 6 - Part 2: days 128/256 (50%) after 1.5s\nDay 6 - Part 2: days done after 1.5s\n"
+        );
+    }
+}
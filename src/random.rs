@@ -0,0 +1,190 @@
+use std::ops::RangeInclusive;
+
+/// A small, seedable xorshift64* generator. Good enough for generating puzzle inputs, and
+/// deterministic so a failing seed can be replayed.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        // A zero state would only ever produce zeros.
+        Self(seed ^ 0x9e37_79b9_7f4a_7c15 | 1)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// A value in `range`, which must not be empty.
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        match (end - start).checked_add(1) {
+            Some(span) => start + self.next_u64() % span,
+            None => self.next_u64(),
+        }
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    /// An index into a collection of `len` items, which must not be empty.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn index(&mut self, len: usize) -> usize {
+        // The result is less than `len`, so it always fits back into a usize.
+        (self.next_u64() % len as u64) as usize
+    }
+
+    /// Shuffles `items` in place.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/// A random but valid puzzle input for `day` with `size` items (depths, commands, diagnostic rows,
+/// boards, lines, fish or crabs), or `None` if there's no generator for the day.
+pub fn input(day: u8, size: usize, rng: &mut Rng) -> Option<String> {
+    let lines: Vec<String> = match day {
+        1 => (0..size)
+            .map(|_| rng.range(0..=10_000).to_string())
+            .collect(),
+        2 => {
+            let mut aim = 0;
+            (0..size)
+                .map(|_| {
+                    let value = rng.range(1..=9);
+                    // Going up past the surface would underflow.
+                    match rng.range(0..=2) {
+                        0 => format!("forward {value}"),
+                        1 if value <= aim => {
+                            aim -= value;
+                            format!("up {value}")
+                        }
+                        _ => {
+                            aim += value;
+                            format!("down {value}")
+                        }
+                    }
+                })
+                .collect()
+        }
+        3 => {
+            // Part 2 only winnows down to one row if no bit position ever agrees across every
+            // remaining row, which holds for every number of some width, so `size` is rounded
+            // down to a power of two.
+            let width = (usize::BITS - size.max(2).leading_zeros() - 1) as usize;
+            let mut rows: Vec<u64> = (0..1_u64 << width).collect();
+            rng.shuffle(&mut rows);
+            rows.iter().map(|row| format!("{row:0width$b}")).collect()
+        }
+        4 => {
+            let mut numbers: Vec<u64> = (0..100).collect();
+            rng.shuffle(&mut numbers);
+            let mut lines = vec![join(&numbers, ",")];
+            for _ in 0..size {
+                rng.shuffle(&mut numbers);
+                lines.push(String::new());
+                lines.extend(numbers[..25].chunks(5).map(|row| {
+                    row.iter()
+                        .map(|n| format!("{n:>2}"))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                }));
+            }
+            lines
+        }
+        5 => (0..size)
+            .map(|_| {
+                let (x1, y1) = (rng.range(0..=999), rng.range(0..=999));
+                let len = rng.range(0..=x1.min(y1));
+                let (x2, y2) = match rng.range(0..=2) {
+                    0 => (rng.range(0..=999), y1),
+                    1 => (x1, rng.range(0..=999)),
+                    _ => (x1 - len, y1 - len),
+                };
+                format!("{x1},{y1} -> {x2},{y2}")
+            })
+            .collect(),
+        6 => vec![join(
+            &(0..size).map(|_| rng.range(1..=5)).collect::<Vec<_>>(),
+            ",",
+        )],
+        7 => {
+            let range = size as u64;
+            vec![join(
+                &(0..size).map(|_| rng.range(0..=range)).collect::<Vec<_>>(),
+                ",",
+            )]
+        }
+        _ => return None,
+    };
+    Some(lines.join("\n"))
+}
+
+fn join(values: &[u64], separator: &str) -> String {
+    values
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rng;
+    use crate::registry::SOLVERS;
+    use crate::run;
+
+    #[test]
+    fn deterministic() {
+        let a: Vec<_> = (0..5)
+            .scan(Rng::new(7), |r, _| Some(r.next_u64()))
+            .collect();
+        let b: Vec<_> = (0..5)
+            .scan(Rng::new(7), |r, _| Some(r.next_u64()))
+            .collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn range() {
+        let mut rng = Rng::new(1);
+        for _ in 0..1000 {
+            assert!((3..=5).contains(&rng.range(3..=5)));
+        }
+        assert_eq!(rng.range(9..=9), 9);
+        rng.range(0..=u64::MAX);
+    }
+
+    #[test]
+    fn shuffle() {
+        let mut items: Vec<_> = (0..20).collect();
+        Rng::new(3).shuffle(&mut items);
+        assert_ne!(items, (0..20).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn input() {
+        let mut rng = Rng::new(40);
+        for solver in SOLVERS {
+            for size in [4, 10, 50] {
+                let input = super::input(solver.day, size, &mut rng).unwrap();
+                let run = run::run(solver, &input);
+                assert!(
+                    run.answer.is_ok(),
+                    "{solver} on {input:?}: {:?}",
+                    run.answer
+                );
+            }
+        }
+        assert!(super::input(8, 10, &mut rng).is_none());
+    }
+}
@@ -0,0 +1,169 @@
+use crate::answer::Answer;
+use crate::cache::Generated;
+use aoc_runner::{ArcStr, Runner};
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+
+pub type Factory = fn(ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+
+/// A single `#[aoc]` solution, addressable by day, part and optional variant name.
+#[derive(Clone, Copy)]
+pub struct Solver {
+    pub day: u8,
+    pub part: u8,
+    pub variant: Option<&'static str>,
+    factory: Factory,
+    cached: Cached,
+}
+
+// The same generator and solver as `factory`, split apart so the generator's output can be cached
+// and the solver's [`Answer`] comes back typed rather than as a `dyn Display`.
+#[derive(Clone, Copy)]
+struct Cached {
+    parse: fn(&str) -> Box<dyn Any>,
+    generate: fn(&str) -> Generated,
+    decode: fn(&[u8]) -> Option<Box<dyn Any>>,
+    solve: fn(&dyn Any) -> Answer,
+}
+
+macro_rules! solvers {
+    ($(#[$cfg:meta] ($day:literal, $part:literal, $variant:expr, $trait:ident::$func:ident, $module:ident::$solver:ident)),* $(,)?) => {
+        &[$(#[$cfg] Solver {
+            day: $day,
+            part: $part,
+            variant: $variant,
+            factory: <crate::Factory as crate::$trait>::$func,
+            cached: Cached {
+                parse: |input| crate::cache::erased::parse(input, crate::$module::input_generator),
+                generate: |input| crate::cache::erased::generate(input, crate::$module::input_generator),
+                decode: |bytes| crate::cache::erased::decode(bytes, crate::$module::input_generator),
+                solve: |parsed| crate::cache::erased::solve(parsed, crate::$module::input_generator, |input| crate::$module::$solver(input)),
+            },
+        }),*]
+    };
+}
+
+/// Every compiled-in solver, in day and part order. A day is left out when its `dayN` feature is
+/// off.
+#[rustfmt::skip]
+pub static SOLVERS: &[Solver] = solvers![
+    #[cfg(feature = "day1")] (1, 1, Some("for_loop"), Day1Part1FOR_LOOP::day1_part1_for_loop, day1::part1_loop),
+    #[cfg(feature = "day1")] (1, 1, Some("fold"), Day1Part1FOLD::day1_part1_fold, day1::part1_fold),
+    #[cfg(feature = "day1")] (1, 1, Some("weird_struct_loop"), Day1Part1WEIRD_STRUCT_LOOP::day1_part1_weird_struct_loop, day1::part1_weird_struct_loop),
+    #[cfg(feature = "day1")] (1, 1, Some("weird_struct_functional"), Day1Part1WEIRD_STRUCT_FUNCTIONAL::day1_part1_weird_struct_functional, day1::part1_weird_struct_functional),
+    #[cfg(feature = "day1")] (1, 2, Some("for_loop"), Day1Part2FOR_LOOP::day1_part2_for_loop, day1::part2_loop),
+    #[cfg(feature = "day1")] (1, 2, Some("fold"), Day1Part2FOLD::day1_part2_fold, day1::part2_fold),
+    #[cfg(feature = "day2")] (2, 1, None, Day2Part1::day2_part1, day2::part1),
+    #[cfg(feature = "day2")] (2, 2, None, Day2Part2::day2_part2, day2::part2),
+    #[cfg(feature = "day3")] (3, 1, None, Day3Part1::day3_part1, day3::part1),
+    #[cfg(feature = "day3")] (3, 2, None, Day3Part2::day3_part2, day3::part2),
+    #[cfg(feature = "day4")] (4, 1, None, Day4Part1::day4_part1, day4::part1),
+    #[cfg(feature = "day4")] (4, 2, None, Day4Part2::day4_part2, day4::part2),
+    #[cfg(feature = "day5")] (5, 1, None, Day5Part1::day5_part1, day5::part1),
+    #[cfg(feature = "day5")] (5, 2, None, Day5Part2::day5_part2, day5::part2),
+    #[cfg(feature = "day6")] (6, 1, None, Day6Part1::day6_part1, day6::part1),
+    #[cfg(feature = "day6")] (6, 2, None, Day6Part2::day6_part2, day6::part2),
+    #[cfg(feature = "day7")] (7, 1, None, Day7Part1::day7_part1, day7::part1),
+    #[cfg(feature = "day7")] (7, 2, None, Day7Part2::day7_part2, day7::part2),
+];
+
+impl Solver {
+    /// Runs the day's generator over `input`, returning a runner ready to solve.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error the generator reports.
+    pub fn generate(&self, input: ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>> {
+        (self.factory)(input)
+    }
+
+    /// Runs the generator, returning its output for [`Solver::solve`].
+    pub(crate) fn parse(&self, input: &str) -> Box<dyn Any> {
+        (self.cached.parse)(input)
+    }
+
+    /// Runs the generator, returning its output along with an encoding of it for the cache.
+    pub(crate) fn generate_encoded(&self, input: &str) -> Generated {
+        (self.cached.generate)(input)
+    }
+
+    /// Decodes generator output previously returned by [`Solver::generate_encoded`].
+    pub(crate) fn decode(&self, encoded: &[u8]) -> Option<Box<dyn Any>> {
+        (self.cached.decode)(encoded)
+    }
+
+    /// Solves generator output from [`Solver::parse`], [`Solver::generate_encoded`] or
+    /// [`Solver::decode`].
+    pub(crate) fn solve(&self, parsed: &dyn Any) -> Answer {
+        (self.cached.solve)(parsed)
+    }
+}
+
+impl fmt::Display for Solver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Day {} - Part {}", self.day, self.part)?;
+        if let Some(variant) = self.variant {
+            write!(f, " - {variant}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds a solver for `day` and `part`. Without a `variant` the first one registered is used.
+#[must_use]
+pub fn find(day: u8, part: u8, variant: Option<&str>) -> Option<&'static Solver> {
+    SOLVERS
+        .iter()
+        .filter(|s| s.day == day && s.part == part)
+        .find(|s| variant.is_none() || s.variant == variant)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(feature = "day1")]
+    fn find() {
+        let solver = super::find(1, 2, Some("fold")).unwrap();
+        assert_eq!(
+            (solver.day, solver.part, solver.variant),
+            (1, 2, Some("fold"))
+        );
+
+        let solver = super::find(1, 1, None).unwrap();
+        assert_eq!(solver.variant, Some("for_loop"));
+
+        assert!(super::find(1, 1, Some("nope")).is_none());
+        assert!(super::find(8, 1, None).is_none());
+    }
+
+    #[test]
+    fn features() {
+        let enabled = [
+            cfg!(feature = "day1"),
+            cfg!(feature = "day2"),
+            cfg!(feature = "day3"),
+            cfg!(feature = "day4"),
+            cfg!(feature = "day5"),
+            cfg!(feature = "day6"),
+            cfg!(feature = "day7"),
+        ];
+        for (day, enabled) in (1..).zip(enabled) {
+            let registered = super::SOLVERS.iter().any(|s| s.day == day);
+            assert_eq!(registered, enabled, "day {day}");
+        }
+    }
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day2"))]
+    fn display() {
+        assert_eq!(
+            super::find(1, 1, Some("fold")).unwrap().to_string(),
+            "Day 1 - Part 1 - fold"
+        );
+        assert_eq!(
+            super::find(2, 2, None).unwrap().to_string(),
+            "Day 2 - Part 2"
+        );
+    }
+}
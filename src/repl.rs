@@ -0,0 +1,464 @@
+#[cfg(feature = "day3")]
+use crate::day3;
+#[cfg(feature = "day4")]
+use crate::day4;
+#[cfg(feature = "day6")]
+use crate::day6;
+#[cfg(feature = "day7")]
+use crate::day7;
+use crate::registry::SOLVERS;
+use crate::run;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::time::Duration;
+
+pub const HISTORY_PATH: &str = "target/aoc-repl/history";
+
+/// How long `solve` gives each solver before giving up on it.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How many lines of history are kept between sessions.
+const MAX_HISTORY: usize = 500;
+
+/// The commands every day has.
+const COMMANDS: &[(&str, &str)] = &[
+    ("help", "lists the commands"),
+    ("solve [PART] [VARIANT]", "runs the solvers on the input"),
+    ("reset", "parses the input again, throwing away any changes"),
+    ("history", "lists the commands entered so far"),
+    (
+        "!! or !N",
+        "runs the last command again, or command N from `history`",
+    ),
+    ("quit", "leaves"),
+];
+
+/// A day's own commands for poking at its parsed input.
+pub(crate) trait Explore {
+    /// Each command's usage and what it does, for `help`.
+    fn commands(&self) -> &'static [(&'static str, &'static str)];
+
+    /// Runs `words`, or returns `None` if they aren't one of the day's commands.
+    fn run(&mut self, words: &[&str]) -> Option<Result<String, String>>;
+}
+
+/// What came of a line given to [`Repl::eval`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reply {
+    Print(String),
+    Quit,
+}
+
+/// An input for one day, parsed and kept in memory to be explored a command at a time.
+pub struct Repl {
+    day: u8,
+    input: String,
+    explorer: Option<Box<dyn Explore>>,
+    history: Vec<String>,
+    timeout: Duration,
+}
+
+impl Repl {
+    /// Parses `input` for `day`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no solver for `day` is compiled in, or if the generator panics.
+    pub fn new(day: u8, input: &str) -> Result<Self, String> {
+        if !SOLVERS.iter().any(|s| s.day == day) {
+            return Err(format!("there's no solver for day {day}"));
+        }
+        let input = input.trim_end_matches('\n').to_string();
+        let explorer = explorer(day, &input)?;
+        Ok(Self {
+            day,
+            input,
+            explorer,
+            history: vec![],
+            timeout: DEFAULT_TIMEOUT,
+        })
+    }
+
+    /// Starts with `history`, as if those commands had already been entered.
+    #[must_use]
+    pub fn with_history(mut self, history: Vec<String>) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// Gives each solver `timeout` to finish in `solve`, rather than [`DEFAULT_TIMEOUT`].
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    #[must_use]
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    /// Runs one line, recording it in the history.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if the command isn't known or can't be carried out.
+    pub fn eval(&mut self, line: &str) -> Result<Reply, String> {
+        let mut line = line.trim().to_string();
+        if line.is_empty() {
+            return Ok(Reply::Print(String::new()));
+        }
+        let mut echo = None;
+        if let Some(n) = line.strip_prefix('!') {
+            let entry = if n == "!" {
+                self.history.last()
+            } else {
+                n.parse::<usize>()
+                    .ok()
+                    .and_then(|n| n.checked_sub(1))
+                    .and_then(|n| self.history.get(n))
+            };
+            line = entry
+                .ok_or_else(|| format!("no {line} in the history"))?
+                .clone();
+            echo = Some(line.clone());
+        }
+        self.history.push(line.clone());
+
+        let words: Vec<_> = line.split_whitespace().collect();
+        let reply = match words[..] {
+            ["help"] => Reply::Print(self.help()),
+            ["quit" | "exit"] => Reply::Quit,
+            ["history"] => Reply::Print(
+                self.history
+                    .iter()
+                    .enumerate()
+                    .map(|(i, line)| format!("{:>4}  {line}", i + 1))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            ["reset"] => {
+                self.explorer = explorer(self.day, &self.input)?;
+                Reply::Print("back to the input as parsed".to_string())
+            }
+            ["solve", ref rest @ ..] => Reply::Print(self.solve(rest)?),
+            _ => {
+                let output = match &mut self.explorer {
+                    Some(explorer) => run::catch(|| explorer.run(&words))
+                        .map_err(|e| format!("{line:?} panicked: {e}, try `reset`"))?,
+                    None => None,
+                };
+                match output {
+                    Some(output) => Reply::Print(output?),
+                    None => return Err(format!("unknown command {:?}, try `help`", words[0])),
+                }
+            }
+        };
+        Ok(match (echo, reply) {
+            (Some(echo), Reply::Print(output)) => Reply::Print(format!("{echo}\n{output}")),
+            (_, reply) => reply,
+        })
+    }
+
+    /// Reads commands from `input` until it ends or one of them quits, writing the replies and
+    /// any errors to `out`. With `prompt`, a prompt is written before each command.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading or writing fails.
+    pub fn run(
+        &mut self,
+        input: impl BufRead,
+        out: &mut impl Write,
+        prompt: bool,
+    ) -> io::Result<()> {
+        let mut lines = input.lines();
+        loop {
+            if prompt {
+                write!(out, "day{}> ", self.day)?;
+                out.flush()?;
+            }
+            let Some(line) = lines.next().transpose()? else {
+                if prompt {
+                    writeln!(out)?;
+                }
+                return Ok(());
+            };
+            match self.eval(&line) {
+                Ok(Reply::Quit) => return Ok(()),
+                Ok(Reply::Print(output)) if output.is_empty() => {}
+                Ok(Reply::Print(output)) => writeln!(out, "{output}")?,
+                Err(e) => writeln!(out, "error: {e}")?,
+            }
+        }
+    }
+
+    fn help(&self) -> String {
+        let day_commands = self.explorer.as_ref().map_or(&[][..], |e| e.commands());
+        let width = COMMANDS
+            .iter()
+            .chain(day_commands)
+            .map(|(usage, _)| usage.len())
+            .max()
+            .unwrap_or(0);
+        let list = |commands: &[(&str, &str)]| {
+            commands
+                .iter()
+                .map(|(usage, what)| format!("  {usage:<width$}  {what}"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        let mut help = list(COMMANDS);
+        if !day_commands.is_empty() {
+            help = format!("day {}:\n{}\n\n{help}", self.day, list(day_commands));
+        }
+        help
+    }
+
+    fn solve(&self, args: &[&str]) -> Result<String, String> {
+        let (part, variant) = match *args {
+            [] => (None, None),
+            [part] => (Some(part), None),
+            [part, variant] => (Some(part), Some(variant)),
+            _ => return Err("usage: solve [PART] [VARIANT]".to_string()),
+        };
+        let part = part.map(|p| number::<u8>(p, "a part")).transpose()?;
+        let solvers: Vec<_> = SOLVERS
+            .iter()
+            .filter(|s| s.day == self.day && part.is_none_or(|p| s.part == p))
+            .filter(|s| variant.is_none() || s.variant == variant)
+            .collect();
+        if solvers.is_empty() {
+            return Err("no solver matches".to_string());
+        }
+        let lines: Vec<_> = solvers
+            .into_iter()
+            .map(|solver| {
+                let options = run::Options {
+                    timeout: Some(self.timeout),
+                    ..run::Options::default()
+                };
+                let run = run::run_with(solver, &self.input, &options);
+                match run.answer {
+                    Ok(answer) => format!(
+                        "{solver}: {} ({:?})",
+                        answer.inline(),
+                        run.generator + run.solver
+                    ),
+                    Err(failure) => format!("{solver}: {failure}"),
+                }
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+}
+
+/// Loads history saved by [`save_history`]. A missing file is an empty history.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but can't be read.
+pub fn load_history(path: &Path) -> io::Result<Vec<String>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents.lines().map(str::to_string).collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(e) => Err(e),
+    }
+}
+
+/// Saves the most recent lines of `history` for the next session.
+///
+/// # Errors
+///
+/// Returns an error if the file or its directory can't be written.
+pub fn save_history(path: &Path, history: &[String]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let recent = &history[history.len().saturating_sub(MAX_HISTORY)..];
+    let mut contents = recent.join("\n");
+    contents.push('\n');
+    fs::write(path, contents)
+}
+
+// Parses `input` into the day's explorer, if it has one. Days without one still have the
+// commands every day has.
+#[cfg_attr(
+    not(any(feature = "day3", feature = "day4", feature = "day6", feature = "day7")),
+    allow(unused_variables)
+)]
+fn explorer(day: u8, input: &str) -> Result<Option<Box<dyn Explore>>, String> {
+    run::catch(|| -> Option<Box<dyn Explore>> {
+        match day {
+            #[cfg(feature = "day3")]
+            3 => Some(Box::new(day3::Explorer::new(day3::input_generator(input)))),
+            #[cfg(feature = "day4")]
+            4 => Some(Box::new(day4::Explorer::new(day4::input_generator(input)))),
+            #[cfg(feature = "day6")]
+            6 => Some(Box::new(day6::Explorer::new(day6::input_generator(input)))),
+            #[cfg(feature = "day7")]
+            7 => Some(Box::new(day7::Explorer::new(day7::input_generator(input)))),
+            _ => None,
+        }
+    })
+    .map_err(|e| format!("couldn't parse the input: {e}"))
+}
+
+/// Parses a command's number argument, saying what it's for if it isn't one.
+pub(crate) fn number<T: std::str::FromStr>(word: &str, what: &str) -> Result<T, String> {
+    word.parse().map_err(|_| format!("{word:?} isn't {what}"))
+}
+
+#[cfg(test)]
+mod tests {
+    // The helpers go unused when the days the tests explore are disabled.
+    #![cfg_attr(
+        not(all(feature = "day1", feature = "day4", feature = "day6", feature = "day7")),
+        allow(dead_code, unused_imports)
+    )]
+
+    use super::{Repl, Reply};
+    use crate::run;
+
+    fn repl(day: u8) -> Repl {
+        let input = std::fs::read_to_string(run::fixture_dir(day).join("example.txt")).unwrap();
+        Repl::new(day, &input).unwrap()
+    }
+
+    fn print(repl: &mut Repl, line: &str) -> String {
+        match repl.eval(line) {
+            Ok(Reply::Print(output)) => output,
+            other => panic!("{line}: {other:?}"),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "day4")]
+    fn new() {
+        assert!(Repl::new(25, "").is_err());
+        let error = Repl::new(4, "nope").err().unwrap();
+        assert!(error.starts_with("couldn't parse the input: "), "{error}");
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn eval() {
+        let mut repl = repl(1);
+        assert!(print(&mut repl, "solve 2 fold").starts_with("Day 1 - Part 2 - fold: 5 ("));
+        assert_eq!(print(&mut repl, "solve 1").lines().count(), 4);
+        assert_eq!(repl.eval("solve 3"), Err("no solver matches".to_string()));
+        assert!(print(&mut repl, "help").contains("solve [PART] [VARIANT]"));
+        assert_eq!(
+            repl.eval("boards"),
+            Err("unknown command \"boards\", try `help`".to_string())
+        );
+        assert_eq!(print(&mut repl, ""), "");
+        assert_eq!(repl.eval("quit"), Ok(Reply::Quit));
+    }
+
+    #[test]
+    #[cfg(feature = "day6")]
+    fn history() {
+        let mut repl = repl(6).with_history(vec!["fish".to_string()]);
+        print(&mut repl, "advance 18");
+        assert_eq!(
+            print(&mut repl, "!1"),
+            format!("fish\n{}", print(&mut repl, "fish"))
+        );
+        assert!(print(&mut repl, "!!").starts_with("fish\nday 18: 26 fish"));
+        assert_eq!(
+            print(&mut repl, "history"),
+            "   1  fish\n   2  advance 18\n   3  fish\n   4  fish\n   5  fish\n   6  history"
+        );
+        assert_eq!(repl.eval("!9"), Err("no !9 in the history".to_string()));
+    }
+
+    #[test]
+    #[cfg(feature = "day4")]
+    fn day_commands() {
+        let mut repl = repl(4);
+        assert!(print(&mut repl, "help").starts_with("day 4:\n  boards"));
+        print(&mut repl, "draw 7");
+        assert!(print(&mut repl, "show board 1").contains("[ 7]"));
+        print(&mut repl, "reset");
+        assert!(!print(&mut repl, "show board 1").contains("[ 7]"));
+    }
+
+    #[test]
+    #[cfg(feature = "day7")]
+    fn run() {
+        let mut repl = repl(7);
+        let mut out = vec![];
+        repl.run(&b"cost at 2\nnope\nquit\ncost at 1\n"[..], &mut out, false)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "part 1: 37 fuel\npart 2: 206 fuel\nerror: unknown command \"nope\", try `help`\n"
+        );
+        assert_eq!(repl.history(), ["cost at 2", "nope", "quit"]);
+    }
+
+    #[test]
+    #[cfg(all(feature = "day3", feature = "day6", feature = "day7"))]
+    fn bad_arguments() {
+        let mut fish = repl(6);
+        let error = fish.eval("fish after 1000").unwrap_err();
+        assert_eq!(error, "there are too many fish to count by then");
+        assert!(fish.eval("advance 1000").is_err());
+        assert!(print(&mut fish, "fish").starts_with("day 0: 5 fish"));
+
+        let mut crabs = repl(7);
+        assert_eq!(
+            print(&mut crabs, &format!("cost at {}", u64::MAX)),
+            "part 1: too much fuel\npart 2: too much fuel"
+        );
+
+        let mut rows = Repl::new(3, "101\n10\n101").unwrap();
+        assert_eq!(
+            rows.eval("rows"),
+            Err("row 2 has 2 bits but row 1 has 3".to_string())
+        );
+        print(&mut rows, "winnow o2");
+        assert!(rows.eval("winnow step").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "day3")]
+    fn panics() {
+        struct Panicky;
+
+        impl super::Explore for Panicky {
+            fn commands(&self) -> &'static [(&'static str, &'static str)] {
+                &[]
+            }
+
+            fn run(&mut self, _words: &[&str]) -> Option<Result<String, String>> {
+                panic!("oops")
+            }
+        }
+
+        let mut repl = Repl::new(3, "101\n101").unwrap();
+        repl.explorer = Some(Box::new(Panicky));
+        assert_eq!(
+            repl.eval("boom"),
+            Err("\"boom\" panicked: oops, try `reset`".to_string())
+        );
+
+        // Duplicate rows never winnow down to one, so part 2 runs until it's given up on.
+        let mut repl = repl.with_timeout(std::time::Duration::from_millis(50));
+        let output = print(&mut repl, "solve 2");
+        assert_eq!(output, "Day 3 - Part 2: TIMED OUT after 50ms");
+    }
+
+    #[test]
+    fn save_history() {
+        let dir = std::env::temp_dir().join(format!("aoc-repl-{}", std::process::id()));
+        let path = dir.join("history");
+        assert!(super::load_history(&path).unwrap().is_empty());
+        let history: Vec<_> = (0..super::MAX_HISTORY + 2).map(|i| i.to_string()).collect();
+        super::save_history(&path, &history).unwrap();
+        assert_eq!(super::load_history(&path).unwrap(), history[2..]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,378 @@
+use crate::alloc;
+use crate::answers::{self, Verdict};
+use crate::dashboard::InputSet;
+#[cfg(feature = "day5")]
+use crate::day5;
+#[cfg(feature = "day6")]
+use crate::day6;
+use crate::registry::{Solver, SOLVERS};
+use crate::run::{self, Options, Run};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+// Larger vent maps are binned down to this many cells a side.
+const HEAT_MAP_SIZE: u16 = 100;
+#[cfg(feature = "day6")]
+const GROWTH_DAYS: u16 = 256;
+
+const STYLE: &str = "
+body { font-family: sans-serif; margin: 2em; color: #222; }
+table { border-collapse: collapse; margin-bottom: 2em; }
+th, td { padding: 0.2em 0.8em; border-bottom: 1px solid #ddd; text-align: left; }
+td.num { text-align: right; font-family: monospace; }
+.correct { color: #1a7f37; }
+.wrong, .failed { color: #cf222e; }
+.unknown { color: #777; }
+svg { border: 1px solid #ddd; background: #fafafa; }
+";
+
+/// Everything from running each solver once over one input set, ready to be written as HTML.
+pub struct Report {
+    input: InputSet,
+    entries: Vec<Entry>,
+    heat_map: Option<HashMap<(u16, u16), u16>>,
+    growth: Option<Vec<u64>>,
+}
+
+struct Entry {
+    solver: &'static Solver,
+    run: Option<Run>,
+    verdict: Verdict,
+    peak_heap: Option<usize>,
+}
+
+impl Report {
+    /// Runs every registered solver over `input`, measuring peak heap use when
+    /// [`alloc::Counting`] is the global allocator, as it is in `aoc` built with `alloc-stats`.
+    #[must_use]
+    pub fn collect(input: InputSet, options: &Options) -> Self {
+        let entries = SOLVERS
+            .iter()
+            .map(|solver| {
+                let path = input.path(solver.day);
+                let Ok(contents) = fs::read_to_string(&path) else {
+                    return Entry {
+                        solver,
+                        run: None,
+                        verdict: Verdict::Unknown,
+                        peak_heap: None,
+                    };
+                };
+                let (run, peak_heap) =
+                    alloc::peak_during(|| run::run_with(solver, &contents, options));
+                let verdict = match &run.answer {
+                    Ok(answer) => answers::for_input(&path)
+                        .unwrap_or_default()
+                        .verify(solver.part, answer),
+                    Err(_) => Verdict::Unknown,
+                };
+                Entry {
+                    solver,
+                    run: Some(run),
+                    verdict,
+                    peak_heap,
+                }
+            })
+            .collect();
+
+        let report = Self {
+            input,
+            entries,
+            heat_map: None,
+            growth: None,
+        };
+        // Only visualize inputs the generator is known to accept, since it panics on anything else.
+        Self {
+            #[cfg(feature = "day5")]
+            heat_map: report
+                .parsed(5)
+                .map(|input| day5::coverage(&day5::input_generator(&input))),
+            #[cfg(feature = "day6")]
+            growth: report
+                .parsed(6)
+                .map(|input| day6::growth(&day6::input_generator(&input), GROWTH_DAYS)),
+            ..report
+        }
+    }
+
+    #[cfg(any(feature = "day5", feature = "day6"))]
+    fn parsed(&self, day: u8) -> Option<String> {
+        let mut runs = self.entries.iter().filter(|e| e.solver.day == day);
+        if !runs.all(|e| e.run.as_ref().is_some_and(|r| r.answer.is_ok())) {
+            return None;
+        }
+        let input = fs::read_to_string(self.input.path(day)).ok()?;
+        Some(input.trim_end_matches('\n').to_string())
+    }
+
+    /// Writes the report as a single HTML page with its styles and charts inline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn write_html(&self, out: &mut impl Write) -> io::Result<()> {
+        let input = match &self.input {
+            InputSet::Puzzle => "puzzle".to_string(),
+            InputSet::Fixture(stem) => stem.clone(),
+        };
+        writeln!(out, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>")?;
+        writeln!(out, "<meta charset=\"utf-8\">")?;
+        writeln!(out, "<title>Advent of code {} report</title>", run::YEAR)?;
+        writeln!(out, "<style>{STYLE}</style>\n</head>\n<body>")?;
+        writeln!(
+            out,
+            "<h1>Advent of code {}</h1>\n<p>Input set: {}</p>",
+            run::YEAR,
+            escape(&input)
+        )?;
+
+        self.write_answers(out)?;
+        self.write_variants(out)?;
+        if let Some(counts) = &self.heat_map {
+            writeln!(out, "<h2>Day 5: hydrothermal vents</h2>")?;
+            writeln!(out, "{}", heat_map_svg(counts))?;
+        }
+        if let Some(growth) = &self.growth {
+            writeln!(out, "<h2>Day 6: lanternfish population</h2>")?;
+            writeln!(out, "{}", growth_svg(growth))?;
+        }
+        writeln!(out, "</body>\n</html>")
+    }
+
+    fn write_answers(&self, out: &mut impl Write) -> io::Result<()> {
+        // Heap use is only counted with the `alloc-stats` feature, so without it there's no column.
+        let heap = self.entries.iter().any(|e| e.peak_heap.is_some());
+        writeln!(out, "<h2>Answers</h2>\n<table>")?;
+        writeln!(
+            out,
+            "<tr><th>Day</th><th>Part</th><th>Variant</th><th>Answer</th><th>Status</th>\
+             <th>Generator</th><th>Solver</th>{}</tr>",
+            if heap { "<th>Peak heap</th>" } else { "" }
+        )?;
+        for entry in &self.entries {
+            let (answer, class, status) = entry.status();
+            let cell = |d: Option<Duration>| d.map(|d| format!("{d:?}")).unwrap_or_default();
+            let heap = if heap {
+                let peak = entry.peak_heap.map(bytes).unwrap_or_default();
+                format!("<td class=\"num\">{peak}</td>")
+            } else {
+                String::new()
+            };
+            writeln!(
+                out,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td class=\"num\">{}</td>\
+                 <td class=\"{class}\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td>\
+                 {heap}</tr>",
+                entry.solver.day,
+                entry.solver.part,
+                entry.solver.variant.unwrap_or("default"),
+                escape(&answer),
+                escape(&status),
+                cell(entry.run.as_ref().map(|r| r.generator)),
+                cell(entry.run.as_ref().map(|r| r.solver)),
+            )?;
+        }
+        writeln!(out, "</table>")?;
+        if !heap {
+            writeln!(
+                out,
+                "<p>Peak heap use isn't shown: it needs <code>aoc</code> built with \
+                 <code>--features alloc-stats</code>.</p>"
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Compares the solvers for every day and part that has more than one.
+    fn write_variants(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut groups: Vec<((u8, u8), Vec<&Entry>)> = vec![];
+        for entry in &self.entries {
+            let key = (entry.solver.day, entry.solver.part);
+            match groups.last_mut() {
+                Some((last, group)) if *last == key => group.push(entry),
+                _ => groups.push((key, vec![entry])),
+            }
+        }
+
+        writeln!(out, "<h2>Variants</h2>")?;
+        for ((day, part), group) in groups.iter().filter(|(_, g)| g.len() > 1) {
+            let fastest = group
+                .iter()
+                .filter_map(|e| e.run.as_ref().map(|r| r.solver))
+                .min()
+                .unwrap_or_default();
+            let first = group[0].run.as_ref().map(|r| &r.answer);
+            writeln!(out, "<h3>Day {day} - Part {part}</h3>\n<table>")?;
+            writeln!(
+                out,
+                "<tr><th>Variant</th><th>Solver</th><th>vs fastest</th><th>Agrees</th></tr>"
+            )?;
+            for entry in group {
+                let Some(run) = &entry.run else { continue };
+                let ratio = run.solver.as_secs_f64() / fastest.as_secs_f64().max(f64::EPSILON);
+                let agrees = first == Some(&run.answer);
+                writeln!(
+                    out,
+                    "<tr><td>{}</td><td class=\"num\">{:?}</td><td class=\"num\">{ratio:.2}x</td>\
+                     <td class=\"{}\">{}</td></tr>",
+                    entry.solver.variant.unwrap_or("default"),
+                    run.solver,
+                    if agrees { "correct" } else { "wrong" },
+                    if agrees { "yes" } else { "no" },
+                )?;
+            }
+            writeln!(out, "</table>")?;
+        }
+        Ok(())
+    }
+}
+
+impl Entry {
+    fn status(&self) -> (String, &'static str, String) {
+        let Some(run) = &self.run else {
+            return (String::new(), "unknown", "no input".to_string());
+        };
+        match (&run.answer, &self.verdict) {
+            (Ok(answer), Verdict::Correct) => (answer.inline(), "correct", "correct".to_string()),
+            (Ok(answer), Verdict::Wrong { expected }) => (
+                answer.inline(),
+                "wrong",
+                format!("wrong, expected {expected}"),
+            ),
+            (Ok(answer), Verdict::Unknown) => {
+                (answer.inline(), "unknown", "unverified".to_string())
+            }
+            (Err(failure), _) => (String::new(), "failed", failure.to_string()),
+        }
+    }
+}
+
+/// Writes `report` to `index.html` in `dir`, creating the directory if needed.
+///
+/// # Errors
+///
+/// Returns an error if the directory or file can't be written.
+pub fn save(report: &Report, dir: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join("index.html");
+    let mut html = vec![];
+    report.write_html(&mut html)?;
+    fs::write(&path, html)?;
+    Ok(path)
+}
+
+fn heat_map_svg(counts: &HashMap<(u16, u16), u16>) -> String {
+    let max = counts
+        .keys()
+        .map(|&(x, y)| x.max(y))
+        .max()
+        .unwrap_or_default();
+    let bin = (max / HEAT_MAP_SIZE) + 1;
+    let size = max / bin + 1;
+
+    let mut bins: HashMap<(u16, u16), u32> = HashMap::new();
+    for (&(x, y), &count) in counts {
+        *bins.entry((x / bin, y / bin)).or_default() += u32::from(count);
+    }
+    let hottest = bins.values().copied().max().unwrap_or(1);
+
+    let mut svg = format!(
+        "<svg viewBox=\"0 0 {size} {size}\" width=\"500\" height=\"500\" shape-rendering=\"crispEdges\">"
+    );
+    for (&(x, y), &count) in &bins {
+        let _ = write!(
+            svg,
+            "<rect x=\"{x}\" y=\"{y}\" width=\"1\" height=\"1\" fill=\"#cf222e\" fill-opacity=\"{:.3}\"/>",
+            f64::from(count) / f64::from(hottest)
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+// Lanternfish grow exponentially, so the chart's y axis is logarithmic.
+#[allow(clippy::cast_precision_loss)]
+fn growth_svg(growth: &[u64]) -> String {
+    const HEIGHT: f64 = 100.0;
+    let top = growth
+        .iter()
+        .map(|&n| (n as f64).log10())
+        .fold(1.0, f64::max);
+    let points: Vec<_> = growth
+        .iter()
+        .enumerate()
+        .map(|(day, &n)| {
+            let y = HEIGHT - (n.max(1) as f64).log10() / top * HEIGHT;
+            format!("{},{y:.2}", day + 1)
+        })
+        .collect();
+    format!(
+        "<svg viewBox=\"0 0 {} {HEIGHT}\" width=\"640\" height=\"250\" preserveAspectRatio=\"none\">\
+         <polyline points=\"{}\" fill=\"none\" stroke=\"#0969da\" stroke-width=\"1\" \
+         vector-effect=\"non-scaling-stroke\"/></svg>\n\
+         <p>{} fish after {} days (log scale).</p>",
+        growth.len(),
+        points.join(" "),
+        growth.last().copied().unwrap_or_default(),
+        growth.len()
+    )
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn bytes(n: usize) -> String {
+    match n {
+        0..1024 => format!("{n} B"),
+        1024..0x10_0000 => format!("{:.1} KiB", n as f64 / 1024.0),
+        _ => format!("{:.1} MiB", n as f64 / f64::from(0x10_0000)),
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day5", feature = "day6"))]
+    fn write_html() {
+        use super::Report;
+        use crate::dashboard::InputSet;
+        use crate::run::Options;
+
+        let report = Report::collect(
+            InputSet::Fixture("example".to_string()),
+            &Options::default(),
+        );
+        let mut html = vec![];
+        report.write_html(&mut html).unwrap();
+        let html = String::from_utf8(html).unwrap();
+
+        assert_eq!(
+            html.matches("<td class=\"correct\">correct</td>").count(),
+            crate::registry::SOLVERS.len()
+        );
+        assert!(html.contains("<h3>Day 1 - Part 1</h3>"));
+        assert!(html.contains("<p>26984457539 fish after 256 days (log scale).</p>"));
+        assert_eq!(html.matches("<svg").count(), 2);
+        // The tests don't install the counting allocator.
+        assert!(!html.contains("Peak heap</th>"));
+        assert!(html.contains("<code>--features alloc-stats</code>"));
+        // Self-contained: nothing to fetch.
+        assert!(!html.contains("http") && !html.contains("src=") && !html.contains("href="));
+    }
+
+    #[test]
+    fn bytes() {
+        assert_eq!(super::bytes(12), "12 B");
+        assert_eq!(super::bytes(1536), "1.5 KiB");
+        assert_eq!(super::bytes(3 << 20), "3.0 MiB");
+    }
+}
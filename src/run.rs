@@ -0,0 +1,438 @@
+use crate::answer::Answer;
+use crate::cache::Cache;
+use crate::cancel;
+use crate::progress::{self, Sink};
+use crate::registry::Solver;
+use crate::trace::{self, span};
+use std::any::Any;
+use std::cell::Cell;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const YEAR: u16 = 2021;
+
+static QUIET_HOOK: Once = Once::new();
+
+thread_local! {
+    // Whether a panic on this thread would be caught by `catch`.
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Failure {
+    Generating(String),
+    Running(String),
+    TimedOut(Duration),
+}
+
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub answer: Result<Answer, Failure>,
+    pub generator: Duration,
+    pub solver: Duration,
+    /// Whether the generator's output came from the [`Cache`] rather than running the generator.
+    pub cache_hit: bool,
+    /// Everything traced while generating and solving, if [`Options::trace`] asked for it.
+    pub trace: Vec<trace::Record>,
+}
+
+/// How [`run_with`] should run a solver.
+#[derive(Clone, Default)]
+pub struct Options {
+    /// Reuse generator output from this cache, as [`run_cached`] does.
+    pub cache: Option<Cache>,
+    /// Run on a worker thread and give up after this long, cancelling the solver.
+    pub timeout: Option<Duration>,
+    /// Capture spans and events into [`Run::trace`]. Only has an effect with the `trace` feature.
+    pub trace: bool,
+    /// Where solvers report their [`progress`].
+    pub progress: Option<Arc<dyn Sink>>,
+}
+
+/// Where the runner looks for a day's puzzle input, relative to the working directory.
+#[must_use]
+pub fn input_path(day: u8) -> PathBuf {
+    PathBuf::from(format!("input/{YEAR}/day{day}.txt"))
+}
+
+/// Where a day's example and reproducer inputs live, relative to the working directory.
+#[must_use]
+pub fn fixture_dir(day: u8) -> PathBuf {
+    PathBuf::from(format!("fixtures/{YEAR}/day{day}"))
+}
+
+/// Every `.txt` input in a day's fixture directory, sorted by name.
+///
+/// # Errors
+///
+/// Returns an error if the fixture directory exists but can't be listed.
+pub fn fixtures(day: u8) -> io::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(fixture_dir(day)) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e),
+    };
+    let mut paths = entries
+        .map(|e| e.map(|e| e.path()))
+        .filter(|p| {
+            p.as_ref()
+                .map_or(true, |p| p.extension().is_some_and(|e| e == "txt"))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    paths.sort();
+    Ok(paths)
+}
+
+/// Generates and solves `input` with `solver`, timing each stage.
+///
+/// Generators and solvers report bad input by panicking, so panics are caught and turned into a
+/// [`Failure`] for the stage they happened in.
+#[must_use]
+pub fn run(solver: &Solver, input: &str) -> Run {
+    let input = input.trim_end_matches('\n');
+
+    let start = Instant::now();
+    let parsed = {
+        let _span = span!("generator", day = solver.day);
+        match catch(|| solver.parse(input)) {
+            Ok(parsed) => parsed,
+            Err(msg) => return failed(Failure::Generating(msg), start.elapsed()),
+        }
+    };
+    let generator = start.elapsed();
+
+    let start = Instant::now();
+    let answer = {
+        let _span = part_span(solver);
+        catch(|| solver.solve(&*parsed)).map_err(Failure::Running)
+    };
+
+    Run {
+        answer,
+        generator,
+        solver: start.elapsed(),
+        cache_hit: false,
+        trace: vec![],
+    }
+}
+
+/// Like [`run`], but reuses the generator's output from `cache` when this input has been seen
+/// before, and stores it there when it hasn't.
+#[must_use]
+pub fn run_cached(solver: &Solver, input: &str, cache: &Cache) -> Run {
+    let input = input.trim_end_matches('\n');
+
+    let start = Instant::now();
+    let generator_span = span!("generator", day = solver.day);
+    let cached = cache
+        .get(solver.day, input)
+        .and_then(|encoded| solver.decode(&encoded));
+    let cache_hit = cached.is_some();
+    trace::event!("cache", hit = cache_hit);
+    let parsed = match cached {
+        Some(parsed) => parsed,
+        None => match catch(|| solver.generate_encoded(input)) {
+            Ok((parsed, encoded)) => {
+                if let Err(e) = cache.put(solver.day, input, &encoded) {
+                    eprintln!("can't write to the generator cache: {e}");
+                }
+                parsed
+            }
+            Err(msg) => return failed(Failure::Generating(msg), start.elapsed()),
+        },
+    };
+    drop(generator_span);
+    let generator = start.elapsed();
+
+    let start = Instant::now();
+    let answer = {
+        let _span = part_span(solver);
+        catch(|| solver.solve(&*parsed)).map_err(Failure::Running)
+    };
+
+    Run {
+        answer,
+        generator,
+        solver: start.elapsed(),
+        cache_hit,
+        trace: vec![],
+    }
+}
+
+/// Runs `solver` according to `options`.
+///
+/// With a timeout the solver runs on its own thread with a [`cancel::Token`] installed as
+/// [`cancel::current`]. If the deadline passes the token is cancelled and a
+/// [`Failure::TimedOut`] is returned straight away; a solver that never checks its token is left
+/// to finish in the background.
+#[must_use]
+pub fn run_with(solver: &'static Solver, input: &str, options: &Options) -> Run {
+    let Some(timeout) = options.timeout else {
+        return run_inline(solver, input, options);
+    };
+
+    let token = cancel::Token::new();
+    let (tx, rx) = mpsc::channel();
+    let worker = {
+        let input = input.to_string();
+        let options = options.clone();
+        let token = token.clone();
+        thread::Builder::new()
+            .name(format!("{solver}"))
+            .spawn(move || {
+                cancel::set_current(Some(token));
+                // The receiver is gone if we've already timed out.
+                let _ = tx.send(run_inline(solver, &input, &options));
+            })
+    };
+
+    let start = Instant::now();
+    if let Err(e) = worker {
+        return failed(Failure::Running(e.to_string()), Duration::ZERO);
+    }
+    rx.recv_timeout(timeout).unwrap_or_else(|_| {
+        token.cancel();
+        Run {
+            answer: Err(Failure::TimedOut(timeout)),
+            generator: Duration::ZERO,
+            solver: start.elapsed(),
+            cache_hit: false,
+            trace: vec![],
+        }
+    })
+}
+
+fn run_inline(solver: &Solver, input: &str, options: &Options) -> Run {
+    let run = || match &options.cache {
+        Some(cache) => run_cached(solver, input, cache),
+        None => run(solver, input),
+    };
+    progress::set_current(options.progress.clone(), || solver.to_string());
+    let run = if options.trace {
+        let (run, records) = trace::capture(run);
+        Run {
+            trace: records,
+            ..run
+        }
+    } else {
+        run()
+    };
+    progress::set_current(None, String::new);
+    run
+}
+
+fn part_span(solver: &Solver) -> trace::Span {
+    span!(
+        "part",
+        day = solver.day,
+        part = solver.part,
+        variant = solver.variant.unwrap_or("default"),
+    )
+}
+
+fn failed(failure: Failure, generator: Duration) -> Run {
+    Run {
+        answer: Err(failure),
+        generator,
+        solver: Duration::ZERO,
+        cache_hit: false,
+        trace: vec![],
+    }
+}
+
+/// Runs `f`, turning a panic into its message. The panic isn't printed, since it's reported as a
+/// [`Failure`] instead.
+pub(crate) fn catch<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    QUIET_HOOK.call_once(|| {
+        // Any other panic still goes to the hook that was there before.
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !CATCHING.get() {
+                previous(info);
+            }
+        }));
+    });
+    let outer = CATCHING.replace(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(outer);
+    result.map_err(|e| panic_message(&*e))
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        (*s).to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else if payload.is::<cancel::Cancelled>() {
+        "cancelled".to_string()
+    } else {
+        "panicked".to_string()
+    }
+}
+
+impl Failure {
+    #[must_use]
+    pub fn stage(&self) -> &'static str {
+        match self {
+            Failure::Generating(_) => "generating",
+            Failure::Running(_) => "running",
+            Failure::TimedOut(_) => "timeout",
+        }
+    }
+
+    #[must_use]
+    pub fn message(&self) -> String {
+        match self {
+            Failure::Generating(msg) | Failure::Running(msg) => msg.clone(),
+            Failure::TimedOut(limit) => format!("gave up after {limit:?}"),
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::TimedOut(limit) => write!(f, "TIMED OUT after {limit:?}"),
+            _ => write!(f, "FAILED while {}: {}", self.stage(), self.message()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry;
+
+    #[test]
+    #[cfg(feature = "day7")]
+    fn run() {
+        let solver = registry::find(7, 1, None).unwrap();
+        let run = super::run(solver, "16,1,2,0,4,2,7,1,2,14\n");
+        assert_eq!(run.answer, Ok(37.into()));
+    }
+
+    #[test]
+    fn fixtures() {
+        for day in 1..=7 {
+            for path in super::fixtures(day).unwrap() {
+                let input = std::fs::read_to_string(&path).unwrap();
+                let known = crate::answers::for_input(&path).unwrap();
+                // Reproducers saved by the minimizer don't have known answers.
+                for solver in registry::SOLVERS
+                    .iter()
+                    .filter(|s| s.day == day && known.get(s.part).is_some())
+                {
+                    let answer = super::run(solver, &input).answer.unwrap();
+                    assert_eq!(
+                        known.verify(solver.part, &answer),
+                        crate::answers::Verdict::Correct,
+                        "{solver} on {}",
+                        path.display()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "day7")]
+    fn run_cached() {
+        let dir = std::env::temp_dir().join(format!("aoc-run-cached-{}", std::process::id()));
+        let cache = crate::cache::Cache::new(&dir);
+
+        for solver in registry::SOLVERS {
+            let path = super::fixture_dir(solver.day).join("example.txt");
+            let input = std::fs::read_to_string(path).unwrap();
+            let expected = super::run(solver, &input).answer;
+
+            let first = super::run_cached(solver, &input, &cache);
+            let second = super::run_cached(solver, &input, &cache);
+            assert_eq!(first.answer, expected, "{solver}");
+            assert_eq!(second.answer, expected, "{solver}");
+            assert!(second.cache_hit, "{solver}");
+        }
+
+        let solver = registry::find(7, 1, None).unwrap();
+        assert!(!super::run_cached(solver, "1,2,4", &cache).cache_hit);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "day3")]
+    fn timeout() {
+        use super::Failure;
+        use std::time::Duration;
+
+        let options = super::Options {
+            timeout: Some(Duration::from_millis(100)),
+            ..super::Options::default()
+        };
+
+        // Duplicate rows never winnow down to one, so this would spin forever.
+        let solver = registry::find(3, 2, None).unwrap();
+        let run = super::run_with(solver, "101\n101\n010", &options);
+        assert_eq!(
+            run.answer,
+            Err(Failure::TimedOut(Duration::from_millis(100)))
+        );
+
+        let run = super::run_with(solver, "00100\n11110\n10110", &options);
+        assert!(run.answer.is_ok());
+    }
+
+    #[cfg(all(feature = "trace", feature = "day4"))]
+    #[test]
+    fn trace() {
+        use crate::trace::Kind;
+
+        let options = super::Options {
+            trace: true,
+            ..super::Options::default()
+        };
+        let solver = registry::find(4, 1, None).unwrap();
+        let input = std::fs::read_to_string("fixtures/2021/day4/example.txt").unwrap();
+        let run = super::run_with(solver, &input, &options);
+
+        let entered: Vec<_> = run
+            .trace
+            .iter()
+            .filter(|r| r.kind != Kind::Event && r.depth == 0)
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(entered, ["generator", "generator", "part", "part"]);
+
+        let win = run.trace.iter().find(|r| r.name == "bingo_win").unwrap();
+        assert_eq!(win.depth, 1);
+        assert!(win.fields.contains(&("score", "4512".to_string())));
+    }
+
+    #[test]
+    fn catch() {
+        let nested = super::catch(|| {
+            assert!(super::CATCHING.get());
+            super::catch(|| panic!("inner")).unwrap_err()
+        });
+        assert_eq!(nested, Ok("inner".to_string()));
+        assert_eq!(
+            super::catch(|| panic!("outer")),
+            Err::<(), _>("outer".to_string())
+        );
+        assert!(!super::CATCHING.get());
+    }
+
+    #[test]
+    #[cfg(feature = "day7")]
+    fn generator_failure() {
+        use super::Failure;
+
+        let solver = registry::find(7, 1, None).unwrap();
+        let run = super::run(solver, "16,one,2");
+        assert!(matches!(run.answer, Err(Failure::Generating(_))));
+    }
+}
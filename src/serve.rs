@@ -0,0 +1,473 @@
+use crate::json::{self, describe};
+use crate::registry::{self, Solver};
+use crate::run::{self, Failure, YEAR};
+use serde_json::{json, Value};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+pub const DEFAULT_ADDR: &str = "127.0.0.1:2021";
+
+/// Bigger request bodies are refused with 413, rather than allocated.
+pub const MAX_BODY: usize = 16 << 20;
+/// Requests with a longer request line and headers, or more header lines, are refused with 431.
+pub const MAX_HEAD: usize = 16 << 10;
+pub const MAX_HEADERS: usize = 100;
+/// How many connections are handled at once before more are turned away with 503.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 16;
+/// How long a client can go without sending anything before its request is dropped with 408.
+pub const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a solver can run before it's cancelled and the request answered with 504.
+pub const DEFAULT_SOLVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A tiny HTTP/1.1 server exposing the solver registry as a JSON API.
+///
+/// * `GET /solvers` lists every registered solver.
+/// * `POST /2021/{day}/{part}?variant={name}` solves the request body, giving the `answer` and
+///   its `answer_type` as `--format json` does.
+///
+/// Each connection is handled on its own thread and closed after one response. Only loopback
+/// addresses can be served on.
+pub struct Server {
+    listener: TcpListener,
+    read_timeout: Duration,
+    solve_timeout: Duration,
+    max_connections: usize,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: Option<String>,
+    body: String,
+}
+
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+impl Server {
+    /// # Errors
+    ///
+    /// Returns an error if `addr` isn't a loopback address or can't be bound.
+    pub fn bind(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        let addrs: Vec<_> = addr.to_socket_addrs()?.collect();
+        if let Some(addr) = addrs.iter().find(|a| !a.ip().is_loopback()) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{addr} isn't a loopback address"),
+            ));
+        }
+        Ok(Self {
+            listener: TcpListener::bind(&addrs[..])?,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            solve_timeout: DEFAULT_SOLVE_TIMEOUT,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+        })
+    }
+
+    #[must_use]
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = timeout;
+        self
+    }
+
+    #[must_use]
+    pub fn with_solve_timeout(mut self, timeout: Duration) -> Self {
+        self.solve_timeout = timeout;
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_connections(mut self, max: usize) -> Self {
+        self.max_connections = max;
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the listener's address can't be read.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts connections until the listener fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error that stopped the accept loop.
+    pub fn serve(self) -> io::Result<()> {
+        let active = Arc::new(AtomicUsize::new(0));
+        loop {
+            let (mut stream, _) = self.listener.accept()?;
+            if let Err(e) = stream.set_read_timeout(Some(self.read_timeout)) {
+                eprintln!("serve: {e}");
+                continue;
+            }
+            if active.fetch_add(1, Ordering::SeqCst) >= self.max_connections {
+                active.fetch_sub(1, Ordering::SeqCst);
+                if let Err(e) = Response::error(503, "too many connections").write_to(&mut stream) {
+                    eprintln!("serve: {e}");
+                }
+                continue;
+            }
+            let solve_timeout = self.solve_timeout;
+            let active = Arc::clone(&active);
+            thread::spawn(move || {
+                if let Err(e) = handle(stream, solve_timeout) {
+                    eprintln!("serve: {e}");
+                }
+                active.fetch_sub(1, Ordering::SeqCst);
+            });
+        }
+    }
+}
+
+fn handle(mut stream: TcpStream, solve_timeout: Duration) -> io::Result<()> {
+    let response = match read_request(&mut stream) {
+        Ok(Ok(request)) => route(&request, solve_timeout),
+        Ok(Err(response)) => response,
+        Err(e)
+            if matches!(
+                e.kind(),
+                io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+            ) =>
+        {
+            Response::error(408, "timed out waiting for the request")
+        }
+        Err(e) => return Err(e),
+    };
+    response.write_to(&mut stream)
+}
+
+// Reads a request, or the response to refuse it with.
+fn read_request(stream: &mut TcpStream) -> io::Result<Result<Request, Response>> {
+    let malformed = || Ok(Err(Response::error(400, "malformed request")));
+    let too_large = || Ok(Err(Response::error(431, "request head too large")));
+    let mut reader = BufReader::new(stream);
+    let mut head = MAX_HEAD as u64;
+
+    let mut line = String::new();
+    if !read_head_line(&mut reader, &mut line, &mut head)? {
+        return too_large();
+    }
+    let mut parts = line.split_ascii_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return malformed();
+    };
+    let (path, query) = match target.split_once('?') {
+        Some((path, query)) => (path.to_string(), Some(query.to_string())),
+        None => (target.to_string(), None),
+    };
+    let method = method.to_string();
+
+    let mut content_length = 0;
+    for headers in 0.. {
+        if !read_head_line(&mut reader, &mut line, &mut head)? {
+            return too_large();
+        }
+        if line.trim_end().is_empty() {
+            break;
+        }
+        if headers == MAX_HEADERS {
+            return too_large();
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                match value.trim().parse() {
+                    Ok(len) => content_length = len,
+                    Err(_) => return malformed(),
+                }
+            }
+        }
+    }
+
+    if content_length > MAX_BODY {
+        return Ok(Err(Response::error(413, "request body too large")));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    let Ok(body) = String::from_utf8(body) else {
+        return malformed();
+    };
+
+    Ok(Ok(Request {
+        method,
+        path,
+        query,
+        body,
+    }))
+}
+
+// Reads a line of the request head into `line`, taking its length out of what's left of `head`.
+// Returns false if the line doesn't fit.
+fn read_head_line(
+    reader: &mut impl BufRead,
+    line: &mut String,
+    head: &mut u64,
+) -> io::Result<bool> {
+    line.clear();
+    if *head == 0 {
+        return Ok(false);
+    }
+    let read = reader.take(*head).read_line(line)?;
+    *head -= read as u64;
+    Ok(line.ends_with('\n') || *head > 0)
+}
+
+fn route(request: &Request, solve_timeout: Duration) -> Response {
+    let segments: Vec<_> = request.path.trim_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["solvers"] if request.method == "GET" => Response::ok(Value::Array(
+            registry::SOLVERS.iter().map(describe).collect(),
+        )),
+        [year, day, part] if *year == YEAR.to_string() => {
+            let (Ok(day), Ok(part)) = (day.parse(), part.parse()) else {
+                return Response::error(404, "no such solver");
+            };
+            match registry::find(day, part, variant(request.query.as_deref())) {
+                Some(_) if request.method != "POST" => Response::error(405, "method not allowed"),
+                Some(solver) => solve(solver, &request.body, solve_timeout),
+                None => Response::error(404, "no such solver"),
+            }
+        }
+        ["solvers"] => Response::error(405, "method not allowed"),
+        _ => Response::error(404, "not found"),
+    }
+}
+
+fn variant(query: Option<&str>) -> Option<&str> {
+    query?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == "variant")
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty())
+}
+
+fn solve(solver: &'static Solver, input: &str, timeout: Duration) -> Response {
+    let options = run::Options {
+        timeout: Some(timeout),
+        ..run::Options::default()
+    };
+    let run = run::run_with(solver, input, &options);
+    let mut body = describe(solver);
+    body["timings"] = json::timings(&run);
+    match run.answer {
+        Ok(answer) => {
+            body["answer"] = answer.to_string().into();
+            body["answer_type"] = answer.kind().into();
+            Response::ok(body)
+        }
+        Err(failure) => {
+            body["error"] = json::error(failure.stage(), &failure.message());
+            let status = match failure {
+                Failure::TimedOut(_) => 504,
+                _ => 422,
+            };
+            Response { status, body }
+        }
+    }
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: json!({ "error": { "message": message } }),
+        }
+    }
+
+    fn write_to(&self, stream: &mut TcpStream) -> io::Result<()> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            408 => "Request Timeout",
+            413 => "Content Too Large",
+            422 => "Unprocessable Entity",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            _ => "",
+        };
+        let body = self.body.to_string();
+        write!(
+            stream,
+            "HTTP/1.1 {} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            self.status,
+            body.len(),
+        )?;
+        stream.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // The helpers go unused when the days the tests solve are disabled.
+    #![cfg_attr(
+        not(all(feature = "day1", feature = "day3")),
+        allow(dead_code, unused_imports)
+    )]
+
+    use super::Server;
+    use serde_json::Value;
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+    use std::thread;
+    use std::time::Duration;
+
+    fn start() -> SocketAddr {
+        serve(Server::bind("127.0.0.1:0").unwrap())
+    }
+
+    fn serve(server: Server) -> SocketAddr {
+        let addr = server.local_addr().unwrap();
+        thread::spawn(move || server.serve());
+        addr
+    }
+
+    fn request(addr: SocketAddr, method: &str, target: &str, body: &str) -> (u16, Value) {
+        let head = format!(
+            "{method} {target} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n",
+            body.len()
+        );
+        send(addr, &(head + body))
+    }
+
+    fn send(addr: SocketAddr, raw: &str) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(raw.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[test]
+    #[cfg(feature = "day1")]
+    fn solvers() {
+        let (status, body) = request(start(), "GET", "/solvers", "");
+        assert_eq!(status, 200);
+        let solvers = body.as_array().unwrap();
+        assert_eq!(solvers.len(), crate::registry::SOLVERS.len());
+        assert_eq!(
+            solvers[1],
+            serde_json::json!({ "day": 1, "part": 1, "variant": "fold" })
+        );
+    }
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day6"))]
+    fn solve() {
+        let addr = start();
+
+        let (status, body) = request(addr, "POST", "/2021/6/1", "3,4,3,1,2");
+        assert_eq!(status, 200);
+        assert_eq!(body["answer"], "5934");
+        assert_eq!(body["answer_type"], "integer");
+        assert!(body["timings"]["generator_ns"].is_u64());
+        assert!(body["timings"]["solver_ns"].is_u64());
+
+        let (status, body) = request(
+            addr,
+            "POST",
+            "/2021/1/2?variant=fold",
+            "199\n200\n208\n210\n200\n207\n240\n269\n260\n263",
+        );
+        assert_eq!(status, 200);
+        assert_eq!(body["variant"], "fold");
+        assert_eq!(body["answer"], "5");
+    }
+
+    #[test]
+    #[cfg(all(feature = "day1", feature = "day7"))]
+    fn errors() {
+        let addr = start();
+
+        let (status, body) = request(addr, "POST", "/2021/7/1", "1,x,3");
+        assert_eq!(status, 422);
+        assert_eq!(body["error"]["stage"], "generating");
+
+        let (status, _) = request(addr, "POST", "/2021/1/1?variant=nope", "1");
+        assert_eq!(status, 404);
+
+        let (status, _) = request(addr, "POST", "/2020/1/1", "1");
+        assert_eq!(status, 404);
+
+        let (status, _) = request(addr, "GET", "/2021/1/1", "");
+        assert_eq!(status, 405);
+
+        let huge = format!(
+            "POST /2021/1/1 HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            usize::MAX
+        );
+        let (status, _) = send(addr, &huge);
+        assert_eq!(status, 413);
+    }
+
+    #[test]
+    fn limits() {
+        let addr = start();
+
+        let mut head = "GET /solvers HTTP/1.1\r\n".to_string();
+        head += &"X-Padding: 1\r\n".repeat(super::MAX_HEADERS);
+        let (status, _) = send(addr, &(head.clone() + "\r\n"));
+        assert_eq!(status, 200);
+        let (status, body) = send(addr, &(head + "X-Padding: 1\r\n"));
+        assert_eq!(status, 431);
+        assert_eq!(body["error"]["message"], "request head too large");
+
+        // A header line that never ends.
+        let mut head = "GET /solvers HTTP/1.1\r\nX-Padding: ".to_string();
+        head += &"1".repeat(super::MAX_HEAD - head.len());
+        let (status, _) = send(addr, &head);
+        assert_eq!(status, 431);
+
+        let addr = serve(Server::bind("127.0.0.1:0").unwrap().with_max_connections(1));
+        let idle = TcpStream::connect(addr).unwrap();
+        let mut response = String::new();
+        TcpStream::connect(addr)
+            .unwrap()
+            .read_to_string(&mut response)
+            .unwrap();
+        assert!(response.starts_with("HTTP/1.1 503 "), "{response}");
+        drop(idle);
+
+        let error = Server::bind("0.0.0.0:0").err().unwrap();
+        assert_eq!(error.to_string(), "0.0.0.0:0 isn't a loopback address");
+    }
+
+    #[test]
+    #[cfg(feature = "day3")]
+    fn timeouts() {
+        let server = Server::bind("127.0.0.1:0")
+            .unwrap()
+            .with_read_timeout(Duration::from_millis(50))
+            .with_solve_timeout(Duration::from_millis(50));
+        let addr = serve(server);
+
+        // Duplicate rows keep day 3's winnowing going until it's cancelled.
+        let (status, body) = request(addr, "POST", "/2021/3/2", "101\n101");
+        assert_eq!(status, 504);
+        assert_eq!(body["error"]["stage"], "timeout");
+
+        // The body never arrives.
+        let (status, _) = send(
+            addr,
+            "POST /2021/1/1 HTTP/1.1\r\nContent-Length: 10\r\n\r\n",
+        );
+        assert_eq!(status, 408);
+    }
+}
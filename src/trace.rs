@@ -0,0 +1,288 @@
+#[cfg(feature = "trace")]
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::time::Duration;
+#[cfg(feature = "trace")]
+use std::time::Instant;
+
+/// One thing that happened while a generator or solver ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub kind: Kind,
+    pub name: &'static str,
+    pub fields: Vec<(&'static str, String)>,
+    /// How many spans this record is nested inside.
+    pub depth: usize,
+    /// Time since the capture started.
+    pub at: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Enter,
+    Event,
+    Exit { elapsed: Duration },
+}
+
+/// Exits its span when dropped. Create one with [`span!`].
+#[must_use]
+pub struct Span {
+    #[cfg(feature = "trace")]
+    name: &'static str,
+    #[cfg(feature = "trace")]
+    start: Instant,
+}
+
+#[cfg(feature = "trace")]
+struct Collector {
+    start: Instant,
+    depth: usize,
+    records: Vec<Record>,
+}
+
+#[cfg(feature = "trace")]
+thread_local! {
+    static COLLECTOR: RefCell<Option<Collector>> = const { RefCell::new(None) };
+}
+
+/// Opens a span that lasts until the returned guard is dropped.
+///
+/// Without the `trace` feature the guard is empty and the fields are never evaluated.
+macro_rules! span {
+    ($name:literal $(, $key:ident = $value:expr)* $(,)?) => {{
+        #[cfg(feature = "trace")]
+        let span = $crate::trace::enter_span($name, || vec![$((stringify!($key), $value.to_string())),*]);
+        #[cfg(not(feature = "trace"))]
+        let span = {
+            if false {
+                $(let _ = &$value;)*
+            }
+            $crate::trace::Span {}
+        };
+        span
+    }};
+}
+
+/// Records an event in the current span.
+///
+/// Without the `trace` feature the fields are never evaluated.
+macro_rules! event {
+    ($name:literal $(, $key:ident = $value:expr)* $(,)?) => {{
+        #[cfg(feature = "trace")]
+        $crate::trace::record_event($name, || vec![$((stringify!($key), $value.to_string())),*]);
+        #[cfg(not(feature = "trace"))]
+        if false {
+            $(let _ = &$value;)*
+        }
+    }};
+}
+
+pub(crate) use {event, span};
+
+/// Runs `f`, collecting everything it traces on this thread.
+#[cfg(not(feature = "trace"))]
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, Vec<Record>) {
+    (f(), vec![])
+}
+
+/// Runs `f`, collecting everything it traces on this thread.
+#[cfg(feature = "trace")]
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, Vec<Record>) {
+    let previous = COLLECTOR.with(|c| {
+        c.borrow_mut().replace(Collector {
+            start: Instant::now(),
+            depth: 0,
+            records: vec![],
+        })
+    });
+    let result = f();
+    let collector = COLLECTOR.with(|c| c.replace(previous));
+    (result, collector.map(|c| c.records).unwrap_or_default())
+}
+
+#[cfg(feature = "trace")]
+fn record(kind: Kind, name: &'static str, fields: Vec<(&'static str, String)>) {
+    COLLECTOR.with(|c| {
+        if let Some(collector) = c.borrow_mut().as_mut() {
+            if let Kind::Exit { .. } = kind {
+                collector.depth = collector.depth.saturating_sub(1);
+            }
+            collector.records.push(Record {
+                kind,
+                name,
+                fields,
+                depth: collector.depth,
+                at: collector.start.elapsed(),
+            });
+            if kind == Kind::Enter {
+                collector.depth += 1;
+            }
+        }
+    });
+}
+
+#[cfg(feature = "trace")]
+fn is_collecting() -> bool {
+    COLLECTOR.with(|c| c.borrow().is_some())
+}
+
+#[cfg(feature = "trace")]
+#[doc(hidden)]
+pub fn enter_span(
+    name: &'static str,
+    fields: impl FnOnce() -> Vec<(&'static str, String)>,
+) -> Span {
+    if is_collecting() {
+        record(Kind::Enter, name, fields());
+    }
+    Span {
+        name,
+        start: Instant::now(),
+    }
+}
+
+#[cfg(feature = "trace")]
+#[doc(hidden)]
+pub fn record_event(name: &'static str, fields: impl FnOnce() -> Vec<(&'static str, String)>) {
+    if is_collecting() {
+        record(Kind::Event, name, fields());
+    }
+}
+
+#[cfg(feature = "trace")]
+impl Drop for Span {
+    fn drop(&mut self) {
+        if is_collecting() {
+            let elapsed = self.start.elapsed();
+            record(Kind::Exit { elapsed }, self.name, vec![]);
+        }
+    }
+}
+
+/// Writes `records` as an indented tree, one line per span and event.
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+pub fn write_tree(out: &mut impl Write, records: &[Record]) -> io::Result<()> {
+    for record in records {
+        let indent = "  ".repeat(record.depth);
+        let name = record.name;
+        let head = match record.kind {
+            Kind::Enter => format!("> {name}"),
+            Kind::Event => format!("- {name}"),
+            Kind::Exit { elapsed } => format!("< {name} ({elapsed:?})"),
+        };
+        write!(out, "{indent}{head}")?;
+        for (key, value) in &record.fields {
+            write!(out, " {key}={value}")?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Writes `records` as JSON, one object per line.
+///
+/// # Errors
+///
+/// Returns an error if writing to `out` fails.
+#[cfg(feature = "trace")]
+pub fn write_json_lines(out: &mut impl Write, records: &[Record]) -> io::Result<()> {
+    for record in records {
+        let (kind, elapsed) = match record.kind {
+            Kind::Enter => ("enter", None),
+            Kind::Event => ("event", None),
+            Kind::Exit { elapsed } => ("exit", Some(elapsed)),
+        };
+        let fields: serde_json::Map<_, _> = record
+            .fields
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), v.clone().into()))
+            .collect();
+        let mut json = serde_json::json!({
+            "kind": kind,
+            "name": record.name,
+            "depth": record.depth,
+            "at_ns": u64::try_from(record.at.as_nanos()).unwrap_or(u64::MAX),
+            "fields": fields,
+        });
+        if let Some(elapsed) = elapsed {
+            json["elapsed_ns"] = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX).into();
+        }
+        writeln!(out, "{json}")?;
+    }
+    Ok(())
+}
+
+#[cfg(all(test, feature = "trace"))]
+mod tests {
+    use super::Kind;
+
+    #[test]
+    fn capture() {
+        let (answer, records) = super::capture(|| {
+            let _outer = span!("outer", day = 4);
+            {
+                let _inner = span!("inner");
+                event!("win", board = 2, score = 4512);
+            }
+            42
+        });
+        assert_eq!(answer, 42);
+
+        let summary: Vec<_> = records
+            .iter()
+            .map(|r| (r.name, r.depth, matches!(r.kind, Kind::Exit { .. })))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("outer", 0, false),
+                ("inner", 1, false),
+                ("win", 2, false),
+                ("inner", 1, true),
+                ("outer", 0, true),
+            ]
+        );
+        assert_eq!(
+            records[2].fields,
+            [("board", "2".to_string()), ("score", "4512".to_string())]
+        );
+    }
+
+    #[test]
+    fn not_capturing() {
+        let _span = span!("ignored");
+        event!("ignored");
+        let ((), records) = super::capture(|| ());
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn render() {
+        let ((), records) = super::capture(|| {
+            let _span = span!("part", part = 1);
+            event!("round", remaining = 3);
+        });
+
+        let mut tree = vec![];
+        super::write_tree(&mut tree, &records).unwrap();
+        let tree = String::from_utf8(tree).unwrap();
+        let lines: Vec<_> = tree.lines().collect();
+        assert_eq!(lines[0], "> part part=1");
+        assert_eq!(lines[1], "  - round remaining=3");
+        assert!(lines[2].starts_with("< part ("));
+
+        let mut json = vec![];
+        super::write_json_lines(&mut json, &records).unwrap();
+        let json: Vec<serde_json::Value> = String::from_utf8(json)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(json[1]["kind"], "event");
+        assert_eq!(json[1]["fields"]["remaining"], "3");
+        assert!(json[2]["elapsed_ns"].is_u64());
+    }
+}
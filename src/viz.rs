@@ -0,0 +1,296 @@
+#[cfg(feature = "day2")]
+use crate::day2;
+#[cfg(feature = "day4")]
+use crate::day4;
+#[cfg(feature = "day5")]
+use crate::day5;
+#[cfg(feature = "day6")]
+use crate::day6;
+use crate::grid::Grid;
+use crate::run;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+const CLEAR: &str = "\x1b[2J\x1b[H";
+const RESET: &str = "\x1b[0m";
+
+pub const DEFAULT_FPS: f64 = 10.0;
+
+/// Days that can be visualized with [`frames`].
+#[rustfmt::skip]
+pub const DAYS: &[u8] = &[
+    #[cfg(feature = "day2")] 2,
+    #[cfg(feature = "day4")] 4,
+    #[cfg(feature = "day5")] 5,
+    #[cfg(feature = "day6")] 6,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colour {
+    Plain,
+    Dim,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+}
+
+/// One picture of an animation: lines of coloured text.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Frame {
+    lines: Vec<Vec<(String, Colour)>>,
+}
+
+/// A fixed-size grid of characters, for days that draw onto a map.
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    cells: Grid<(char, Colour)>,
+}
+
+/// Shows frames one after another in a terminal.
+#[derive(Debug, Clone, Copy)]
+pub struct Player {
+    pub fps: f64,
+    pub ansi: bool,
+}
+
+impl Colour {
+    fn code(self) -> &'static str {
+        match self {
+            Colour::Plain => "",
+            Colour::Dim => "\x1b[2m",
+            Colour::Red => "\x1b[1;31m",
+            Colour::Green => "\x1b[1;32m",
+            Colour::Yellow => "\x1b[1;33m",
+            Colour::Blue => "\x1b[34m",
+        }
+    }
+}
+
+impl Frame {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends `text` to the current line.
+    pub fn push(&mut self, text: impl Into<String>, colour: Colour) -> &mut Self {
+        let span = (text.into(), colour);
+        match self.lines.last_mut() {
+            Some(line) => line.push(span),
+            None => self.lines.push(vec![span]),
+        }
+        self
+    }
+
+    /// Starts a new line.
+    pub fn newline(&mut self) -> &mut Self {
+        self.lines.push(vec![]);
+        self
+    }
+
+    /// The frame without any colour.
+    #[must_use]
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for line in &self.lines {
+            for (span, _) in line {
+                text.push_str(span);
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn write(&self, out: &mut impl Write, ansi: bool) -> io::Result<()> {
+        for line in &self.lines {
+            for (span, colour) in line {
+                match colour {
+                    Colour::Plain => write!(out, "{span}")?,
+                    _ if !ansi => write!(out, "{span}")?,
+                    _ => write!(out, "{}{span}{RESET}", colour.code())?,
+                }
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+impl Canvas {
+    #[must_use]
+    pub fn new(width: usize, height: usize, background: char) -> Self {
+        Self {
+            cells: Grid::new(width, height, (background, Colour::Dim)),
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> char {
+        self.cells[(x, y)].0
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, c: char, colour: Colour) {
+        self.cells[(x, y)] = (c, colour);
+    }
+
+    /// Appends the canvas to `frame`, starting on a new line.
+    pub fn draw(&self, frame: &mut Frame) {
+        for row in self.cells.rows() {
+            frame.newline();
+            // Runs of one colour go out as one span, to keep the escape codes down.
+            let mut run = String::new();
+            let Some(&(_, mut colour)) = row.first() else {
+                continue;
+            };
+            for &(c, next) in row {
+                if next != colour {
+                    frame.push(std::mem::take(&mut run), colour);
+                    colour = next;
+                }
+                run.push(c);
+            }
+            frame.push(run, colour);
+        }
+    }
+}
+
+/// Maps `value` in `0..=max` onto `0..size`.
+#[must_use]
+pub fn scale(value: u64, max: u64, size: usize) -> usize {
+    let size = size as u64;
+    let scaled = value.min(max) * size.saturating_sub(1) / max.max(1);
+    usize::try_from(scaled).unwrap_or_default()
+}
+
+impl Player {
+    /// Shows each frame in turn, clearing the screen in between when `ansi` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn play(&self, frames: &[Frame], out: &mut impl Write) -> io::Result<()> {
+        let delay = Duration::from_secs_f64(1.0 / self.fps.max(0.01));
+        for (i, frame) in frames.iter().enumerate() {
+            if self.ansi {
+                write!(out, "{CLEAR}")?;
+            } else if i > 0 {
+                writeln!(out)?;
+            }
+            frame.write(out, self.ansi)?;
+            out.flush()?;
+            if i + 1 < frames.len() {
+                thread::sleep(delay);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Player {
+    fn default() -> Self {
+        Self {
+            fps: DEFAULT_FPS,
+            ansi: false,
+        }
+    }
+}
+
+/// The animation for `day`'s `input`, or `None` if the day has no visualization.
+///
+/// # Errors
+///
+/// Returns the message the day's generator panicked with if `input` isn't valid for the day.
+#[cfg_attr(
+    not(any(feature = "day2", feature = "day4", feature = "day5", feature = "day6")),
+    allow(unused_variables)
+)]
+pub fn frames(day: u8, input: &str) -> Result<Option<Vec<Frame>>, String> {
+    let input = input.trim_end_matches('\n');
+    run::catch(|| match day {
+        #[cfg(feature = "day2")]
+        2 => Some(day2::frames(&day2::input_generator(input))),
+        #[cfg(feature = "day4")]
+        4 => Some(day4::frames(&day4::input_generator(input))),
+        #[cfg(feature = "day5")]
+        5 => Some(day5::frames(&day5::input_generator(input))),
+        #[cfg(feature = "day6")]
+        6 => Some(day6::frames(&day6::input_generator(input))),
+        _ => None,
+    })
+}
+
+/// Writes each frame to `frame-NNNN.txt` in `dir` without colour, returning how many were written.
+///
+/// # Errors
+///
+/// Returns an error if the directory or a frame can't be written.
+pub fn dump(frames: &[Frame], dir: &Path) -> io::Result<usize> {
+    fs::create_dir_all(dir)?;
+    for (i, frame) in frames.iter().enumerate() {
+        fs::write(dir.join(format!("frame-{i:04}.txt")), frame.text())?;
+    }
+    Ok(frames.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Canvas, Colour, Frame};
+
+    #[test]
+    fn frame() {
+        let mut frame = Frame::new();
+        frame.push("a", Colour::Plain).push("b", Colour::Red);
+        let mut canvas = Canvas::new(3, 2, '.');
+        canvas.set(1, 1, '#', Colour::Green);
+        canvas.draw(&mut frame);
+        assert_eq!(frame.text(), "ab\n...\n.#.\n");
+
+        let mut out = vec![];
+        frame.write(&mut out, true).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("a\x1b[1;31mb\x1b[0m\n\x1b[2m...\x1b[0m\n"));
+    }
+
+    #[test]
+    fn scale() {
+        assert_eq!(super::scale(0, 10, 5), 0);
+        assert_eq!(super::scale(10, 10, 5), 4);
+        assert_eq!(super::scale(5, 10, 5), 2);
+        assert_eq!(super::scale(3, 0, 5), 0);
+        assert_eq!(super::scale(3, 10, 0), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "day6")]
+    fn play_and_dump() {
+        use super::Player;
+
+        let frames = super::frames(6, "3,4,3,1,2").unwrap().unwrap();
+        let player = Player {
+            fps: 1000.0,
+            ansi: false,
+        };
+        let mut out = vec![];
+        player.play(&frames[..2], &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("{}\n{}", frames[0].text(), frames[1].text())
+        );
+
+        let dir = std::env::temp_dir().join(format!("aoc-viz-{}", std::process::id()));
+        assert_eq!(super::dump(&frames, &dir).unwrap(), frames.len());
+        let last = std::fs::read_to_string(dir.join(format!("frame-{:04}.txt", frames.len() - 1)));
+        assert_eq!(last.unwrap(), frames[frames.len() - 1].text());
+        std::fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!(super::frames(7, "1,2"), Ok(None));
+        assert!(super::frames(6, "3,x").is_err());
+    }
+}
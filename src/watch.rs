@@ -0,0 +1,231 @@
+use crate::answer::Answer;
+use crate::answers::{self, Verdict};
+use crate::detect;
+use crate::registry::{Solver, SOLVERS};
+use crate::run::{self, Failure, Options};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+pub const POLL_INTERVAL: Duration = Duration::from_millis(250);
+pub const DEBOUNCE: Duration = Duration::from_millis(300);
+
+type Stamp = (Option<SystemTime>, u64);
+
+/// Re-runs every solver for a day whenever its input or fixtures change on disk.
+///
+/// Changes are found by polling modification times and sizes, and a burst of edits only triggers
+/// one run once the files have been quiet for [`DEBOUNCE`].
+pub struct Watch {
+    day: u8,
+    input: PathBuf,
+    fixtures: PathBuf,
+    stamps: BTreeMap<PathBuf, Stamp>,
+    previous: HashMap<(PathBuf, usize), Result<Answer, Failure>>,
+    options: Options,
+}
+
+impl Watch {
+    #[must_use]
+    pub fn new(day: u8, input: PathBuf, fixtures: PathBuf) -> Self {
+        let mut watch = Self {
+            day,
+            input,
+            fixtures,
+            stamps: BTreeMap::new(),
+            previous: HashMap::new(),
+            options: Options::default(),
+        };
+        watch.stamps = watch.snapshot();
+        watch
+    }
+
+    /// Runs solvers with `options`, e.g. to reuse cached generator output or enforce a timeout.
+    #[must_use]
+    pub fn with_options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Reports once, then again after every change, forever.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn run(mut self, out: &mut impl Write) -> io::Result<()> {
+        loop {
+            self.report(out)?;
+            self.wait_for_change();
+        }
+    }
+
+    fn wait_for_change(&mut self) {
+        while !self.changed() {
+            thread::sleep(POLL_INTERVAL);
+        }
+        loop {
+            thread::sleep(DEBOUNCE);
+            if !self.changed() {
+                return;
+            }
+        }
+    }
+
+    fn changed(&mut self) -> bool {
+        let stamps = self.snapshot();
+        if stamps == self.stamps {
+            false
+        } else {
+            self.stamps = stamps;
+            true
+        }
+    }
+
+    fn snapshot(&self) -> BTreeMap<PathBuf, Stamp> {
+        let mut paths = vec![self.input.clone(), answers::path_for(&self.input)];
+        if let Ok(entries) = fs::read_dir(&self.fixtures) {
+            paths.extend(entries.filter_map(|e| Some(e.ok()?.path())));
+        }
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                let meta = fs::metadata(&path).ok()?;
+                Some((path, (meta.modified().ok(), meta.len())))
+            })
+            .collect()
+    }
+
+    fn inputs(&self) -> Vec<PathBuf> {
+        let mut inputs = vec![self.input.clone()];
+        inputs.extend(
+            self.stamps
+                .keys()
+                .filter(|p| p.starts_with(&self.fixtures))
+                .filter(|p| p.extension().is_some_and(|e| e == "txt"))
+                .cloned(),
+        );
+        inputs
+    }
+
+    /// Runs every solver for the day over the input and each fixture, printing the answers next to
+    /// what the previous report said and what the answer registry expects.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to `out` fails.
+    pub fn report(&mut self, out: &mut impl Write) -> io::Result<()> {
+        for path in self.inputs() {
+            writeln!(out, "== {}", path.display())?;
+            let input = match fs::read_to_string(&path) {
+                Ok(input) => input,
+                Err(e) => {
+                    writeln!(out, "can't read input: {e}\n")?;
+                    continue;
+                }
+            };
+            if let Some(warning) = detect::mismatch(self.day, &input) {
+                writeln!(out, "warning: {warning}")?;
+            }
+            let known = answers::for_input(&path).unwrap_or_default();
+
+            for (i, solver) in SOLVERS
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| s.day == self.day)
+            {
+                let run = run::run_with(solver, &input, &self.options);
+                let previous = self.previous.insert((path.clone(), i), run.answer.clone());
+                write_answer(out, solver, &run.answer, previous.as_ref(), &known)?;
+                writeln!(
+                    out,
+                    "\tgenerator: {:?}{}, runner: {:?}",
+                    run.generator,
+                    if run.cache_hit { " (cached)" } else { "" },
+                    run.solver
+                )?;
+            }
+            writeln!(out)?;
+        }
+        out.flush()
+    }
+}
+
+fn write_answer(
+    out: &mut impl Write,
+    solver: &Solver,
+    answer: &Result<Answer, Failure>,
+    previous: Option<&Result<Answer, Failure>>,
+    known: &answers::Known,
+) -> io::Result<()> {
+    match answer {
+        Ok(answer) => write!(out, "{solver}: {}", answer.inline())?,
+        Err(failure) => write!(out, "{solver}: {failure}")?,
+    }
+    match previous {
+        None => {}
+        Some(previous) if previous == answer => write!(out, " (unchanged)")?,
+        Some(Ok(previous)) => write!(out, " (was {})", previous.inline())?,
+        Some(Err(_)) => write!(out, " (previously failed)")?,
+    }
+    if let Ok(answer) = answer {
+        match known.verify(solver.part, answer) {
+            Verdict::Correct => write!(out, " [correct]")?,
+            Verdict::Wrong { expected } => write!(out, " [wrong, expected {expected}]")?,
+            Verdict::Unknown => {}
+        }
+    }
+    writeln!(out)
+}
+
+/// The watch targets for `day` when none are given on the command line.
+#[must_use]
+pub fn default_paths(day: u8) -> (PathBuf, PathBuf) {
+    (run::input_path(day), run::fixture_dir(day))
+}
+
+#[cfg(all(test, feature = "day7"))]
+mod tests {
+    use super::Watch;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-watch-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("fixtures")).unwrap();
+        dir
+    }
+
+    fn render(watch: &mut Watch) -> String {
+        let mut out = vec![];
+        watch.report(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn report() {
+        let dir = temp_dir("report");
+        let input = dir.join("day7.txt");
+        fs::write(&input, "16,1,2,0,4,2,7,1,2,14").unwrap();
+        fs::write(dir.join("day7.answers"), "part1: 37\npart2: 169").unwrap();
+        fs::write(dir.join("fixtures/small.txt"), "1,2,3").unwrap();
+
+        let mut watch = Watch::new(7, input.clone(), dir.join("fixtures"));
+        let out = render(&mut watch);
+        assert!(out.contains("Day 7 - Part 1: 37 [correct]\n"));
+        assert!(out.contains("Day 7 - Part 2: 168 [wrong, expected 169]\n"));
+        assert!(out.contains("small.txt\nDay 7 - Part 1: 2\n"));
+
+        fs::write(&input, "16,1,2,0,4,2,7,1,2,14,30").unwrap();
+        assert!(watch.changed());
+        assert!(!watch.changed());
+        let out = render(&mut watch);
+        assert!(out.contains("Day 7 - Part 1: 65 (was 37) [wrong, expected 37]\n"));
+        assert!(out.contains("small.txt\nDay 7 - Part 1: 2 (unchanged)\n"));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}